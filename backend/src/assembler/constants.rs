@@ -0,0 +1,5 @@
+pub const MNEMONICS: [&str; 29] = [
+    "SCD", "SCU", "CLS", "RET", "SCR", "SCL", "EXIT", "LOW", "HIGH", "JP", "CALL", "SE", "SNE",
+    "LD", "ADD", "OR", "AND", "XOR", "SUB", "SHR", "SUBN", "SHL", "RND", "DRW", "SKP", "SKNP",
+    "PLANE", "AUDIO", "PITCH",
+];
@@ -0,0 +1,173 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum AssemblerError {
+    UnexpectedCharacter {
+        character: char,
+        line: usize,
+        column: usize,
+    },
+    UnexpectedToken {
+        token: String,
+        line: usize,
+        column: usize,
+    },
+    InvalidNumber {
+        token: String,
+        line: usize,
+        column: usize,
+    },
+    UnknownMnemonic {
+        mnemonic: String,
+        line: usize,
+        column: usize,
+    },
+    InvalidOperands {
+        mnemonic: String,
+        line: usize,
+        column: usize,
+    },
+    UndefinedSymbol {
+        symbol: String,
+        line: usize,
+        column: usize,
+    },
+    DuplicateSymbol {
+        symbol: String,
+        line: usize,
+        column: usize,
+    },
+    ValueOutOfRange {
+        value: i32,
+        line: usize,
+        column: usize,
+    },
+    AddressOutOfRange {
+        address: i32,
+        line: usize,
+        column: usize,
+    },
+    OverlappingOrigin {
+        address: i32,
+        line: usize,
+        column: usize,
+    },
+    UnexpectedEnd {
+        line: usize,
+        column: usize,
+    },
+    UnbalancedBlock {
+        token: String,
+        line: usize,
+        column: usize,
+    },
+    AssertionFailed {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+}
+
+impl AssemblerError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AssemblerError::UnexpectedCharacter { .. } => "UnexpectedCharacter",
+            AssemblerError::UnexpectedToken { .. } => "UnexpectedToken",
+            AssemblerError::InvalidNumber { .. } => "InvalidNumber",
+            AssemblerError::UnknownMnemonic { .. } => "UnknownMnemonic",
+            AssemblerError::InvalidOperands { .. } => "InvalidOperands",
+            AssemblerError::UndefinedSymbol { .. } => "UndefinedSymbol",
+            AssemblerError::DuplicateSymbol { .. } => "DuplicateSymbol",
+            AssemblerError::ValueOutOfRange { .. } => "ValueOutOfRange",
+            AssemblerError::AddressOutOfRange { .. } => "AddressOutOfRange",
+            AssemblerError::OverlappingOrigin { .. } => "OverlappingOrigin",
+            AssemblerError::UnexpectedEnd { .. } => "UnexpectedEnd",
+            AssemblerError::UnbalancedBlock { .. } => "UnbalancedBlock",
+            AssemblerError::AssertionFailed { .. } => "AssertionFailed",
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        match self {
+            AssemblerError::UnexpectedCharacter { line, .. }
+            | AssemblerError::UnexpectedToken { line, .. }
+            | AssemblerError::InvalidNumber { line, .. }
+            | AssemblerError::UnknownMnemonic { line, .. }
+            | AssemblerError::InvalidOperands { line, .. }
+            | AssemblerError::UndefinedSymbol { line, .. }
+            | AssemblerError::DuplicateSymbol { line, .. }
+            | AssemblerError::ValueOutOfRange { line, .. }
+            | AssemblerError::AddressOutOfRange { line, .. }
+            | AssemblerError::OverlappingOrigin { line, .. }
+            | AssemblerError::UnexpectedEnd { line, .. }
+            | AssemblerError::UnbalancedBlock { line, .. }
+            | AssemblerError::AssertionFailed { line, .. } => *line,
+        }
+    }
+
+    pub fn column(&self) -> usize {
+        match self {
+            AssemblerError::UnexpectedCharacter { column, .. }
+            | AssemblerError::UnexpectedToken { column, .. }
+            | AssemblerError::InvalidNumber { column, .. }
+            | AssemblerError::UnknownMnemonic { column, .. }
+            | AssemblerError::InvalidOperands { column, .. }
+            | AssemblerError::UndefinedSymbol { column, .. }
+            | AssemblerError::DuplicateSymbol { column, .. }
+            | AssemblerError::ValueOutOfRange { column, .. }
+            | AssemblerError::AddressOutOfRange { column, .. }
+            | AssemblerError::OverlappingOrigin { column, .. }
+            | AssemblerError::UnexpectedEnd { column, .. }
+            | AssemblerError::UnbalancedBlock { column, .. }
+            | AssemblerError::AssertionFailed { column, .. } => *column,
+        }
+    }
+}
+
+impl fmt::Display for AssemblerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line(), self.column())?;
+
+        match self {
+            AssemblerError::UnexpectedCharacter { character, .. } => {
+                write!(f, "Unexpected character {:?}", character)
+            },
+            AssemblerError::UnexpectedToken { token, .. } => {
+                write!(f, "Unexpected token {:?}", token)
+            },
+            AssemblerError::InvalidNumber { token, .. } => {
+                write!(f, "Invalid number {:?}", token)
+            },
+            AssemblerError::UnknownMnemonic { mnemonic, .. } => {
+                write!(f, "Unknown mnemonic {:?}", mnemonic)
+            },
+            AssemblerError::InvalidOperands { mnemonic, .. } => {
+                write!(f, "Invalid operands for {}", mnemonic)
+            },
+            AssemblerError::UndefinedSymbol { symbol, .. } => {
+                write!(f, "Undefined symbol {:?}", symbol)
+            },
+            AssemblerError::DuplicateSymbol { symbol, .. } => {
+                write!(f, "Symbol {:?} is already defined", symbol)
+            },
+            AssemblerError::ValueOutOfRange { value, .. } => {
+                write!(f, "Value {} is out of range", value)
+            },
+            AssemblerError::AddressOutOfRange { address, .. } => {
+                write!(f, "Address {:#06X} is out of range", address)
+            },
+            AssemblerError::OverlappingOrigin { address, .. } => {
+                write!(f, "Origin {:#06X} overlaps the code before it", address)
+            },
+            AssemblerError::UnexpectedEnd { .. } => write!(f, "Unexpected end of input"),
+            AssemblerError::UnbalancedBlock { token, .. } => {
+                write!(f, "Unbalanced {:?}", token)
+            },
+            AssemblerError::AssertionFailed { message, .. } => {
+                write!(f, "Assertion failed: {}", message)
+            },
+        }
+    }
+}
+
+impl std::error::Error for AssemblerError {}
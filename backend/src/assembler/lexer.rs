@@ -0,0 +1,93 @@
+use super::errors::AssemblerError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Word,
+    Number,
+    Comma,
+    Colon,
+    LeftBracket,
+    RightBracket,
+    Plus,
+    Minus,
+}
+
+#[derive(Clone, Debug)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub column: usize,
+}
+
+impl Token {
+    pub fn is_word(&self, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(word)
+    }
+}
+
+fn is_word_char(character: char) -> bool {
+    character.is_ascii_alphanumeric() || matches!(character, '_' | '.')
+}
+
+pub fn tokenize(source: &str, line: usize) -> Result<Vec<Token>, AssemblerError> {
+    let characters: Vec<char> = source.chars().collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut position = 0;
+
+    while position < characters.len() {
+        let character = characters[position];
+        let start = position;
+        position += 1;
+
+        let kind = match character {
+            ';' => break,
+            c if c.is_whitespace() => continue,
+            ',' => TokenKind::Comma,
+            '[' => TokenKind::LeftBracket,
+            ']' => TokenKind::RightBracket,
+            '+' => TokenKind::Plus,
+            '-' => TokenKind::Minus,
+            // A colon glued to the previous word ends a label, otherwise it starts a directive
+            ':' if characters
+                .get(position)
+                .is_some_and(|c| c.is_ascii_alphabetic())
+                && !(start > 0 && is_word_char(characters[start - 1])) =>
+            {
+                while characters.get(position).is_some_and(|c| is_word_char(*c)) {
+                    position += 1;
+                }
+
+                TokenKind::Word
+            },
+            ':' => TokenKind::Colon,
+            '0'..='9' | '#' | '$' => {
+                while characters.get(position).is_some_and(|c| is_word_char(*c)) {
+                    position += 1;
+                }
+
+                TokenKind::Number
+            },
+            c if is_word_char(c) => {
+                while characters.get(position).is_some_and(|c| is_word_char(*c)) {
+                    position += 1;
+                }
+
+                TokenKind::Word
+            },
+
+            _ => Err(AssemblerError::UnexpectedCharacter {
+                character,
+                line,
+                column: start + 1,
+            })?,
+        };
+
+        tokens.push(Token {
+            kind,
+            text: characters[start..position].iter().collect(),
+            column: start + 1,
+        });
+    }
+
+    Ok(tokens)
+}
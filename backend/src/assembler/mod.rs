@@ -0,0 +1,791 @@
+use std::collections::{HashMap, HashSet};
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::debugger::condition::parse_number;
+use crate::disasm::instruction::Instruction;
+use crate::engine::constants::{START_ADDRESS, XO_CHIP_MEMORY_SIZE};
+use crate::symbols::{SymbolFormat, SymbolMap};
+
+use constants::MNEMONICS;
+use errors::AssemblerError;
+use lexer::{Token, TokenKind, tokenize};
+
+pub mod constants;
+pub mod errors;
+pub mod lexer;
+pub mod octo;
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Debug)]
+pub struct Label {
+    pub name: String,
+    pub address: u16,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Debug)]
+pub struct Monitor {
+    pub name: String,
+    pub address: u16,
+    pub length: u16,
+    pub format: Option<String>,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Debug)]
+pub struct Assembly {
+    pub rom: Vec<u8>,
+    pub labels: Vec<Label>,
+    pub breakpoints: Vec<Label>,
+    pub monitors: Vec<Monitor>,
+}
+
+impl Assembly {
+    pub fn symbols(&self) -> SymbolMap {
+        let mut symbols = SymbolMap::new();
+
+        for label in &self.labels {
+            symbols.insert(label.address, &label.name);
+        }
+
+        symbols
+    }
+}
+
+#[wasm_bindgen]
+impl Assembly {
+    #[wasm_bindgen]
+    pub fn export_symbols(&self, format: Option<SymbolFormat>) -> String {
+        self.symbols().export(format.unwrap_or_default())
+    }
+}
+
+enum Term {
+    Number(i32),
+    Symbol(String),
+}
+
+struct Expression {
+    terms: Vec<(i32, Term, usize)>,
+    column: usize,
+}
+
+enum Operand {
+    Register(u8),
+    Range(u8, u8),
+    Index,
+    IndirectIndex,
+    DelayTimer,
+    SoundTimer,
+    Key,
+    Font,
+    LargeFont,
+    Bcd,
+    Flags,
+    Long(Expression),
+    Value(Expression),
+}
+
+enum Statement {
+    Instruction {
+        mnemonic: String,
+        operands: Vec<Operand>,
+    },
+    Data {
+        values: Vec<Expression>,
+        width: usize,
+    },
+    Space,
+}
+
+struct Item {
+    statement: Statement,
+    address: usize,
+    size: usize,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Default)]
+struct Assembler {
+    symbols: HashMap<String, i32>,
+    aliases: HashMap<String, u8>,
+    labels: Vec<Label>,
+    items: Vec<Item>,
+    address: usize,
+    end: usize,
+    // Lines of `LD I, address` that need F000 NNNN, found by a previous pass
+    long: HashSet<usize>,
+}
+
+fn unexpected(token: &Token, line: usize) -> AssemblerError {
+    AssemblerError::UnexpectedToken {
+        token: token.text.clone(),
+        line,
+        column: token.column,
+    }
+}
+
+fn parse_expression(
+    tokens: &[Token],
+    line: usize,
+    column: usize,
+) -> Result<Expression, AssemblerError> {
+    let mut terms = Vec::new();
+    let mut sign = 1;
+    let mut expect_term = true;
+
+    for token in tokens {
+        match token.kind {
+            TokenKind::Plus | TokenKind::Minus if expect_term && terms.is_empty() => {
+                sign = if token.kind == TokenKind::Minus {
+                    -1
+                } else {
+                    1
+                };
+            },
+            TokenKind::Plus | TokenKind::Minus if !expect_term => {
+                sign = if token.kind == TokenKind::Minus {
+                    -1
+                } else {
+                    1
+                };
+                expect_term = true;
+            },
+            TokenKind::Number if expect_term => {
+                let number = parse_number(&token.text).ok_or(AssemblerError::InvalidNumber {
+                    token: token.text.clone(),
+                    line,
+                    column: token.column,
+                })?;
+
+                terms.push((sign, Term::Number(number as i32), token.column));
+                expect_term = false;
+            },
+            TokenKind::Word if expect_term => {
+                terms.push((sign, Term::Symbol(token.text.clone()), token.column));
+                expect_term = false;
+            },
+
+            _ => Err(unexpected(token, line))?,
+        }
+    }
+
+    if expect_term {
+        Err(AssemblerError::UnexpectedToken {
+            token: String::new(),
+            line,
+            column: tokens.last().map_or(column, |token| token.column),
+        })?;
+    }
+
+    Ok(Expression {
+        terms,
+        column: tokens.first().map_or(column, |token| token.column),
+    })
+}
+
+fn split_operands(tokens: &[Token], line: usize) -> Result<Vec<&[Token]>, AssemblerError> {
+    if tokens.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    for (index, token) in tokens.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|index| tokens[index].kind);
+
+        if token.kind == TokenKind::Comma
+            && (previous.is_none_or(|kind| kind == TokenKind::Comma) || index == tokens.len() - 1)
+        {
+            Err(unexpected(token, line))?;
+        }
+    }
+
+    Ok(tokens
+        .split(|token| token.kind == TokenKind::Comma)
+        .collect())
+}
+
+impl Assembler {
+    fn register(&self, token: &Token) -> Option<u8> {
+        if token.kind != TokenKind::Word {
+            return None;
+        }
+
+        let upper = token.text.to_ascii_uppercase();
+
+        match upper.strip_prefix('V') {
+            Some(register) if register.len() == 1 => u8::from_str_radix(register, 16).ok(),
+
+            _ => self.aliases.get(&token.text).copied(),
+        }
+    }
+
+    fn parse_operand(&self, tokens: &[Token], line: usize) -> Result<Operand, AssemblerError> {
+        match tokens {
+            [open, index, close]
+                if open.kind == TokenKind::LeftBracket
+                    && index.is_word("I")
+                    && close.kind == TokenKind::RightBracket =>
+            {
+                return Ok(Operand::IndirectIndex);
+            },
+            [first, minus, last] if minus.kind == TokenKind::Minus => {
+                if let (Some(x), Some(y)) = (self.register(first), self.register(last)) {
+                    return Ok(Operand::Range(x, y));
+                }
+            },
+            [token] => {
+                if let Some(register) = self.register(token) {
+                    return Ok(Operand::Register(register));
+                }
+
+                if token.kind == TokenKind::Word {
+                    match token.text.to_ascii_uppercase().as_str() {
+                        "I" => return Ok(Operand::Index),
+                        "DT" => return Ok(Operand::DelayTimer),
+                        "ST" => return Ok(Operand::SoundTimer),
+                        "K" => return Ok(Operand::Key),
+                        "F" => return Ok(Operand::Font),
+                        "HF" => return Ok(Operand::LargeFont),
+                        "B" => return Ok(Operand::Bcd),
+                        "R" => return Ok(Operand::Flags),
+                        _ => {},
+                    }
+                }
+            },
+            [long, rest @ ..] if long.is_word("LONG") => {
+                return Ok(Operand::Long(parse_expression(rest, line, long.column)?));
+            },
+
+            _ => {},
+        }
+
+        Ok(Operand::Value(parse_expression(tokens, line, 0)?))
+    }
+
+    fn evaluate(&self, expression: &Expression, line: usize) -> Result<i32, AssemblerError> {
+        let mut value: i32 = 0;
+
+        for (sign, term, column) in &expression.terms {
+            let term = match term {
+                Term::Number(number) => *number,
+                Term::Symbol(symbol) => {
+                    *self
+                        .symbols
+                        .get(symbol)
+                        .ok_or(AssemblerError::UndefinedSymbol {
+                            symbol: symbol.clone(),
+                            line,
+                            column: *column,
+                        })?
+                },
+            };
+
+            value = value.wrapping_add(sign * term);
+        }
+
+        Ok(value)
+    }
+
+    fn value(
+        &self,
+        expression: &Expression,
+        line: usize,
+        min: i32,
+        max: i32,
+    ) -> Result<i32, AssemblerError> {
+        let value = self.evaluate(expression, line)?;
+
+        if value < min || value > max {
+            Err(AssemblerError::ValueOutOfRange {
+                value,
+                line,
+                column: expression.column,
+            })?;
+        }
+
+        Ok(value)
+    }
+
+    fn define(&mut self, token: &Token, value: i32, line: usize) -> Result<(), AssemblerError> {
+        if token.kind != TokenKind::Word {
+            Err(unexpected(token, line))?;
+        }
+
+        if self.symbols.insert(token.text.clone(), value).is_some() {
+            Err(AssemblerError::DuplicateSymbol {
+                symbol: token.text.clone(),
+                line,
+                column: token.column,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn set_address(
+        &mut self,
+        address: i32,
+        line: usize,
+        column: usize,
+    ) -> Result<(), AssemblerError> {
+        if address < START_ADDRESS as i32 || address > XO_CHIP_MEMORY_SIZE as i32 {
+            Err(AssemblerError::AddressOutOfRange {
+                address,
+                line,
+                column,
+            })?;
+        }
+
+        self.address = address as usize;
+        self.end = self.end.max(self.address);
+
+        Ok(())
+    }
+
+    fn parse_line(&mut self, source: &str, line: usize) -> Result<(), AssemblerError> {
+        let tokens = tokenize(source, line)?;
+        let mut tokens = tokens.as_slice();
+
+        // label:
+        if let [label, colon, rest @ ..] = tokens
+            && colon.kind == TokenKind::Colon
+        {
+            self.define(label, self.address as i32, line)?;
+            self.labels.push(Label {
+                name: label.text.clone(),
+                address: self.address as u16,
+            });
+            tokens = rest;
+        }
+
+        let [first, rest @ ..] = tokens else {
+            return Ok(());
+        };
+
+        // NAME EQU value
+        if let [equ, value @ ..] = rest
+            && equ.is_word("EQU")
+        {
+            let expression = parse_expression(value, line, equ.column)?;
+            let value = self.evaluate(&expression, line)?;
+
+            return self.define(first, value, line);
+        }
+
+        if first.kind != TokenKind::Word {
+            Err(unexpected(first, line))?;
+        }
+
+        let mnemonic = first.text.to_ascii_uppercase();
+        let column = first.column;
+
+        let (statement, size) = match mnemonic.trim_start_matches([':', '.']) {
+            "ALIAS" => {
+                let [name, register] = rest
+                    .iter()
+                    .filter(|token| token.kind != TokenKind::Comma)
+                    .collect::<Vec<_>>()[..]
+                else {
+                    return Err(AssemblerError::InvalidOperands {
+                        mnemonic,
+                        line,
+                        column,
+                    });
+                };
+
+                let register = self.register(register).ok_or(unexpected(register, line))?;
+                self.aliases.insert(name.text.clone(), register);
+
+                return Ok(());
+            },
+            "CONST" => {
+                let [name, value @ ..] = rest else {
+                    return Err(AssemblerError::InvalidOperands {
+                        mnemonic,
+                        line,
+                        column,
+                    });
+                };
+
+                let expression = parse_expression(value, line, name.column)?;
+                let value = self.evaluate(&expression, line)?;
+
+                return self.define(name, value, line);
+            },
+            "ORG" => {
+                let expression = parse_expression(rest, line, column)?;
+                let address = self.evaluate(&expression, line)?;
+
+                // Going back would overwrite what was already assembled
+                if address < self.address as i32 {
+                    Err(AssemblerError::OverlappingOrigin {
+                        address,
+                        line,
+                        column: expression.column,
+                    })?;
+                }
+
+                return self.set_address(address, line, expression.column);
+            },
+            "DS" => {
+                let expression = parse_expression(rest, line, column)?;
+                let size = self.value(&expression, line, 0, XO_CHIP_MEMORY_SIZE as i32)?;
+
+                (Statement::Space, size as usize)
+            },
+            "DB" | "DW" => {
+                let width = if mnemonic == "DB" { 1 } else { 2 };
+                let values = split_operands(rest, line)?
+                    .into_iter()
+                    .map(|tokens| parse_expression(tokens, line, column))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let size = values.len() * width;
+
+                (Statement::Data { values, width }, size)
+            },
+            _ if MNEMONICS.contains(&mnemonic.as_str()) => {
+                let operands = split_operands(rest, line)?
+                    .into_iter()
+                    .map(|tokens| self.parse_operand(tokens, line))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                // F000 NNNN is only used when the address does not fit in ANNN
+                let size = match (mnemonic.as_str(), operands.as_slice()) {
+                    ("LD", [Operand::Index, Operand::Long(_)]) => 4,
+                    ("LD", [Operand::Index, Operand::Value(expression)])
+                        if self.long.contains(&line)
+                            || self
+                                .evaluate(expression, line)
+                                .is_ok_and(|address| address > 0xFFF) =>
+                    {
+                        4
+                    },
+
+                    _ => 2,
+                };
+
+                (Statement::Instruction { mnemonic, operands }, size)
+            },
+
+            _ => Err(AssemblerError::UnknownMnemonic {
+                mnemonic: first.text.clone(),
+                line,
+                column,
+            })?,
+        };
+
+        self.items.push(Item {
+            statement,
+            address: self.address,
+            size,
+            line,
+            column,
+        });
+
+        self.set_address((self.address + size) as i32, line, column)
+    }
+
+    // Lines of `LD I, address` assembled as ANNN with an address that does not fit in it
+    fn long_lines(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .filter(|item| item.size == 2)
+            .filter(|item| match &item.statement {
+                Statement::Instruction { mnemonic, operands } if mnemonic == "LD" => {
+                    match operands.as_slice() {
+                        [Operand::Index, Operand::Value(expression)] => self
+                            .evaluate(expression, item.line)
+                            .is_ok_and(|address| address > 0xFFF),
+
+                        _ => false,
+                    }
+                },
+
+                _ => false,
+            })
+            .map(|item| item.line)
+            .collect()
+    }
+
+    fn encode(&self, item: &Item) -> Result<Vec<u8>, AssemblerError> {
+        let line = item.line;
+
+        let (mnemonic, operands) = match &item.statement {
+            Statement::Instruction { mnemonic, operands } => (mnemonic, operands),
+            Statement::Data { values, width } => {
+                let mut bytes = Vec::with_capacity(item.size);
+
+                for value in values {
+                    if *width == 1 {
+                        bytes.push(self.value(value, line, -0x80, 0xFF)? as u8);
+                    } else {
+                        let word = self.value(value, line, -0x8000, 0xFFFF)? as u16;
+                        bytes.extend_from_slice(&word.to_be_bytes());
+                    }
+                }
+
+                return Ok(bytes);
+            },
+            Statement::Space => return Ok(vec![0; item.size]),
+        };
+
+        let nibble = |e: &Expression| self.value(e, line, 0, 0xF).map(|v| v as u8);
+        let byte = |e: &Expression| self.value(e, line, -0x80, 0xFF).map(|v| v as u8);
+        let address = |e: &Expression| self.value(e, line, 0, 0xFFF).map(|v| v as u16);
+        let long = |e: &Expression| self.value(e, line, 0, 0xFFFF).map(|v| v as u16);
+
+        use Operand::*;
+
+        let instruction = match (mnemonic.as_str(), operands.as_slice()) {
+            ("SCD", [Value(n)]) => Instruction::ScrollDown(nibble(n)?),
+            ("SCU", [Value(n)]) => Instruction::ScrollUp(nibble(n)?),
+            ("CLS", []) => Instruction::Clear,
+            ("RET", []) => Instruction::Return,
+            ("SCR", []) => Instruction::ScrollRight,
+            ("SCL", []) => Instruction::ScrollLeft,
+            ("EXIT", []) => Instruction::Exit,
+            ("LOW", []) => Instruction::LowResolution,
+            ("HIGH", []) => Instruction::HighResolution,
+            ("JP", [Value(nnn)]) => Instruction::Jump(address(nnn)?),
+            ("JP", [Register(0), Value(nnn)]) => Instruction::JumpOffset(address(nnn)?),
+            ("CALL", [Value(nnn)]) => Instruction::Call(address(nnn)?),
+            ("SE", [Register(x), Value(nn)]) => Instruction::SkipEqualByte(*x, byte(nn)?),
+            ("SE", [Register(x), Register(y)]) => Instruction::SkipEqual(*x, *y),
+            ("SNE", [Register(x), Value(nn)]) => Instruction::SkipNotEqualByte(*x, byte(nn)?),
+            ("SNE", [Register(x), Register(y)]) => Instruction::SkipNotEqual(*x, *y),
+            ("LD", [IndirectIndex, Range(x, y)]) => Instruction::StoreRange(*x, *y),
+            ("LD", [Range(x, y), IndirectIndex]) => Instruction::LoadRange(*x, *y),
+            ("LD", [Register(x), Value(nn)]) => Instruction::LoadByte(*x, byte(nn)?),
+            ("LD", [Register(x), Register(y)]) => Instruction::Move(*x, *y),
+            ("LD", [Index, Long(nnnn)]) => Instruction::LoadLongIndex(long(nnnn)?),
+            ("LD", [Index, Value(nnnn)]) if item.size == 4 => {
+                Instruction::LoadLongIndex(long(nnnn)?)
+            },
+            ("LD", [Index, Value(nnn)]) => Instruction::LoadIndex(address(nnn)?),
+            ("LD", [Register(x), DelayTimer]) => Instruction::LoadDelay(*x),
+            ("LD", [Register(x), Key]) => Instruction::WaitKey(*x),
+            ("LD", [DelayTimer, Register(x)]) => Instruction::SetDelay(*x),
+            ("LD", [SoundTimer, Register(x)]) => Instruction::SetSound(*x),
+            ("LD", [Font, Register(x)]) => Instruction::LoadFont(*x),
+            ("LD", [LargeFont, Register(x)]) => Instruction::LoadLargeFont(*x),
+            ("LD", [Bcd, Register(x)]) => Instruction::Bcd(*x),
+            ("LD", [IndirectIndex, Register(x)]) => Instruction::StoreRegisters(*x),
+            ("LD", [Register(x), IndirectIndex]) => Instruction::LoadRegisters(*x),
+            ("LD", [Flags, Register(x)]) => Instruction::StoreFlags(*x),
+            ("LD", [Register(x), Flags]) => Instruction::LoadFlags(*x),
+            ("ADD", [Register(x), Value(nn)]) => Instruction::AddByte(*x, byte(nn)?),
+            ("ADD", [Register(x), Register(y)]) => Instruction::Add(*x, *y),
+            ("ADD", [Index, Register(x)]) => Instruction::AddIndex(*x),
+            ("OR", [Register(x), Register(y)]) => Instruction::Or(*x, *y),
+            ("AND", [Register(x), Register(y)]) => Instruction::And(*x, *y),
+            ("XOR", [Register(x), Register(y)]) => Instruction::Xor(*x, *y),
+            ("SUB", [Register(x), Register(y)]) => Instruction::Sub(*x, *y),
+            ("SHR", [Register(x)]) => Instruction::ShiftRight(*x, *x),
+            ("SHR", [Register(x), Register(y)]) => Instruction::ShiftRight(*x, *y),
+            ("SUBN", [Register(x), Register(y)]) => Instruction::SubNegated(*x, *y),
+            ("SHL", [Register(x)]) => Instruction::ShiftLeft(*x, *x),
+            ("SHL", [Register(x), Register(y)]) => Instruction::ShiftLeft(*x, *y),
+            ("RND", [Register(x), Value(nn)]) => Instruction::Random(*x, byte(nn)?),
+            ("DRW", [Register(x), Register(y), Value(n)]) => Instruction::Draw(*x, *y, nibble(n)?),
+            ("SKP", [Register(x)]) => Instruction::SkipKey(*x),
+            ("SKNP", [Register(x)]) => Instruction::SkipNotKey(*x),
+            ("PLANE", [Value(n)]) => Instruction::Plane(nibble(n)?),
+            ("AUDIO", []) => Instruction::Audio,
+            ("PITCH", [Register(x)]) => Instruction::Pitch(*x),
+
+            _ => Err(AssemblerError::InvalidOperands {
+                mnemonic: mnemonic.clone(),
+                line,
+                column: item.column,
+            })?,
+        };
+
+        Ok(instruction.encode())
+    }
+}
+
+pub fn assemble(source: &str) -> Result<Assembly, AssemblerError> {
+    let mut long = HashSet::new();
+
+    // Addresses of forward references are unknown while parsing, so the source is parsed again
+    // whenever one of them turns out not to fit in ANNN. Lines only ever grow, so this ends
+    let assembler = loop {
+        let mut assembler = Assembler {
+            address: START_ADDRESS,
+            end: START_ADDRESS,
+            long: long.clone(),
+            ..Default::default()
+        };
+
+        for (number, line) in source.lines().enumerate() {
+            assembler.parse_line(line, number + 1)?;
+        }
+
+        let lines = assembler.long_lines();
+
+        if lines.is_empty() {
+            break assembler;
+        }
+
+        long.extend(lines);
+    };
+
+    let mut rom = vec![0; assembler.end - START_ADDRESS];
+
+    for item in &assembler.items {
+        let bytes = assembler.encode(item)?;
+        let offset = item.address - START_ADDRESS;
+
+        rom[offset..offset + bytes.len()].copy_from_slice(&bytes);
+    }
+
+    Ok(Assembly {
+        rom,
+        labels: assembler.labels,
+        breakpoints: Vec::new(),
+        monitors: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::assemble;
+    use super::errors::AssemblerError;
+    use crate::disasm::instruction::Instruction;
+    use crate::engine::quirks::Platform;
+
+    const INSTRUCTIONS: [Instruction; 53] = [
+        Instruction::ScrollDown(4),
+        Instruction::ScrollUp(3),
+        Instruction::Clear,
+        Instruction::Return,
+        Instruction::ScrollRight,
+        Instruction::ScrollLeft,
+        Instruction::Exit,
+        Instruction::LowResolution,
+        Instruction::HighResolution,
+        Instruction::Jump(0x234),
+        Instruction::Call(0xABC),
+        Instruction::SkipEqualByte(0x1, 0x42),
+        Instruction::SkipNotEqualByte(0xA, 0xFF),
+        Instruction::SkipEqual(0x2, 0x3),
+        Instruction::StoreRange(0x1, 0x5),
+        Instruction::LoadRange(0x5, 0x1),
+        Instruction::LoadByte(0xF, 0x00),
+        Instruction::AddByte(0x7, 0x80),
+        Instruction::Move(0x0, 0xE),
+        Instruction::Or(0x1, 0x2),
+        Instruction::And(0x3, 0x4),
+        Instruction::Xor(0x5, 0x6),
+        Instruction::Add(0x7, 0x8),
+        Instruction::Sub(0x9, 0xA),
+        Instruction::ShiftRight(0xB, 0xC),
+        Instruction::SubNegated(0xD, 0xE),
+        Instruction::ShiftLeft(0xF, 0x0),
+        Instruction::SkipNotEqual(0x4, 0x5),
+        Instruction::LoadIndex(0x300),
+        Instruction::JumpOffset(0x400),
+        Instruction::Random(0x6, 0x0F),
+        Instruction::Draw(0x1, 0x2, 0xF),
+        Instruction::SkipKey(0x3),
+        Instruction::SkipNotKey(0x4),
+        Instruction::LoadLongIndex(0x1234),
+        Instruction::Plane(2),
+        Instruction::Audio,
+        Instruction::LoadDelay(0x5),
+        Instruction::WaitKey(0x6),
+        Instruction::SetDelay(0x7),
+        Instruction::SetSound(0x8),
+        Instruction::AddIndex(0x9),
+        Instruction::LoadFont(0xA),
+        Instruction::LoadLargeFont(0xB),
+        Instruction::Bcd(0xC),
+        Instruction::Pitch(0xD),
+        Instruction::StoreRegisters(0xE),
+        Instruction::LoadRegisters(0xF),
+        Instruction::StoreFlags(0x3),
+        Instruction::LoadFlags(0x4),
+        Instruction::Data(0x0123),
+        Instruction::Data(0xFFFF),
+        Instruction::Data(0x5AB9),
+    ];
+
+    #[test]
+    fn round_trip() {
+        for instruction in INSTRUCTIONS {
+            let source = instruction.classic();
+            let assembly = assemble(&source).unwrap();
+
+            assert_eq!(assembly.rom, instruction.encode(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn decode_encoded() {
+        for instruction in INSTRUCTIONS {
+            let bytes = instruction.encode();
+            let opcode = u16::from_be_bytes([bytes[0], bytes[1]]);
+            let next = bytes
+                .get(2..4)
+                .map(|next| u16::from_be_bytes([next[0], next[1]]));
+
+            assert_eq!(
+                Instruction::decode(opcode, next, Platform::XoChip),
+                instruction,
+                "{:#06X}",
+                opcode
+            );
+        }
+    }
+
+    #[test]
+    fn labels() {
+        let assembly = assemble(
+            "start: LD V0, 0x01
+                    CALL sub
+                    JP start
+             sub:   RET",
+        )
+        .unwrap();
+
+        assert_eq!(
+            assembly.rom,
+            [0x60, 0x01, 0x22, 0x06, 0x12, 0x00, 0x00, 0xEE]
+        );
+    }
+
+    #[test]
+    fn forward_long_index() {
+        // The first LD only needs F000 NNNN once the label after it is known
+        let assembly = assemble(
+            "       LD I, data
+                    LD I, start
+             start: JP start
+                    ORG 0x1000
+             data:  DB 0xAA",
+        )
+        .unwrap();
+
+        assert_eq!(
+            assembly.rom[..8],
+            [0xF0, 0x00, 0x10, 0x00, 0xA2, 0x06, 0x12, 0x06]
+        );
+        assert_eq!(assembly.rom[0x1000 - 0x200], 0xAA);
+    }
+
+    #[test]
+    fn origin() {
+        assert!(assemble("ORG 0x300\nDB 1\nORG 0x301").is_ok());
+        assert!(matches!(
+            assemble("ORG 0x300\nDB 1, 2\nORG 0x301"),
+            Err(AssemblerError::OverlappingOrigin {
+                address: 0x301,
+                line: 3,
+                ..
+            })
+        ));
+    }
+}
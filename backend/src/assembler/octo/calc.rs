@@ -0,0 +1,146 @@
+use std::f64::consts;
+
+use crate::assembler::errors::AssemblerError;
+
+use super::lexer::Token;
+
+fn unexpected(token: &Token) -> AssemblerError {
+    AssemblerError::UnexpectedToken {
+        token: token.text.clone(),
+        line: token.line,
+        column: token.column,
+    }
+}
+
+// Octo evaluates expressions from right to left and every operator has the same precedence
+pub struct Calc<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    end: &'a Token,
+    name: &'a dyn Fn(&Token) -> Result<f64, AssemblerError>,
+    memory: &'a dyn Fn(f64) -> f64,
+}
+
+impl<'a> Calc<'a> {
+    pub fn evaluate(
+        tokens: &'a [Token],
+        end: &'a Token,
+        name: &'a dyn Fn(&Token) -> Result<f64, AssemblerError>,
+        memory: &'a dyn Fn(f64) -> f64,
+    ) -> Result<f64, AssemblerError> {
+        let mut calc = Self {
+            tokens,
+            position: 0,
+            end,
+            name,
+            memory,
+        };
+
+        let value = calc.expression()?;
+
+        if let Some(token) = calc.tokens.get(calc.position) {
+            Err(unexpected(token))?;
+        }
+
+        Ok(value)
+    }
+
+    fn next(&mut self) -> Result<&'a Token, AssemblerError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .ok_or(AssemblerError::UnexpectedEnd {
+                line: self.end.line,
+                column: self.end.column,
+            })?;
+
+        self.position += 1;
+
+        Ok(token)
+    }
+
+    fn expression(&mut self) -> Result<f64, AssemblerError> {
+        let left = self.term()?;
+
+        let operator = match self.tokens.get(self.position) {
+            Some(token) if token.text != ")" => token,
+
+            _ => return Ok(left),
+        };
+
+        self.position += 1;
+        let right = self.expression()?;
+
+        let (a, b) = (left as i64, right as i64);
+
+        Ok(match operator.text.as_str() {
+            "+" => left + right,
+            "-" => left - right,
+            "*" => left * right,
+            "/" => left / right,
+            "%" => left % right,
+            "&" => (a & b) as f64,
+            "|" => (a | b) as f64,
+            "^" => (a ^ b) as f64,
+            "<<" => a.wrapping_shl(b as u32) as f64,
+            ">>" => a.wrapping_shr(b as u32) as f64,
+            "pow" => left.powf(right),
+            "min" => left.min(right),
+            "max" => left.max(right),
+            "<" => (left < right) as u8 as f64,
+            "<=" => (left <= right) as u8 as f64,
+            ">" => (left > right) as u8 as f64,
+            ">=" => (left >= right) as u8 as f64,
+            "==" => (left == right) as u8 as f64,
+            "!=" => (left != right) as u8 as f64,
+
+            _ => Err(unexpected(operator))?,
+        })
+    }
+
+    fn term(&mut self) -> Result<f64, AssemblerError> {
+        let token = self.next()?;
+
+        let unary: fn(f64) -> f64 = match token.text.as_str() {
+            "(" => {
+                let value = self.expression()?;
+                let close = self.next()?;
+
+                if close.text != ")" {
+                    Err(unexpected(close))?;
+                }
+
+                return Ok(value);
+            },
+            "@" => {
+                let address = self.term()?;
+
+                return Ok((self.memory)(address));
+            },
+            "PI" => return Ok(consts::PI),
+            "E" => return Ok(consts::E),
+            "-" => |value| -value,
+            "~" => |value| !(value as i64) as f64,
+            "!" => |value| (value == 0.0) as u8 as f64,
+            "sin" => f64::sin,
+            "cos" => f64::cos,
+            "tan" => f64::tan,
+            "exp" => f64::exp,
+            "log" => f64::ln,
+            "abs" => f64::abs,
+            "sqrt" => f64::sqrt,
+            "sign" => f64::signum,
+            "ceil" => f64::ceil,
+            "floor" => f64::floor,
+
+            _ => {
+                return match token.number() {
+                    Some(number) => Ok(number as f64),
+                    None => (self.name)(token),
+                };
+            },
+        };
+
+        Ok(unary(self.term()?))
+    }
+}
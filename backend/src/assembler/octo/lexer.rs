@@ -0,0 +1,73 @@
+use crate::assembler::errors::AssemblerError;
+use crate::debugger::condition::parse_number;
+
+#[derive(Clone, Debug)]
+pub struct Token {
+    pub text: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Token {
+    pub fn number(&self) -> Option<i64> {
+        match self.text.strip_prefix('-') {
+            Some(number) => parse_number(number).map(|number| -(number as i64)),
+            None => parse_number(&self.text).map(|number| number as i64),
+        }
+    }
+
+    pub fn string(&self) -> Option<&str> {
+        self.text.strip_prefix('"')?.strip_suffix('"')
+    }
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<Token>, AssemblerError> {
+    let mut tokens = Vec::new();
+
+    for (number, line) in source.lines().enumerate() {
+        let characters: Vec<char> = line.chars().collect();
+        let mut position = 0;
+
+        while position < characters.len() {
+            let start = position;
+
+            match characters[position] {
+                '#' => break,
+                c if c.is_whitespace() => {
+                    position += 1;
+                    continue;
+                },
+                '"' => {
+                    position += 1;
+
+                    while characters.get(position).is_some_and(|c| *c != '"') {
+                        position += 1;
+                    }
+
+                    if position == characters.len() {
+                        Err(AssemblerError::UnexpectedEnd {
+                            line: number + 1,
+                            column: start + 1,
+                        })?;
+                    }
+
+                    position += 1;
+                },
+
+                _ => {
+                    while characters.get(position).is_some_and(|c| !c.is_whitespace()) {
+                        position += 1;
+                    }
+                },
+            }
+
+            tokens.push(Token {
+                text: characters[start..position].iter().collect(),
+                line: number + 1,
+                column: start + 1,
+            });
+        }
+    }
+
+    Ok(tokens)
+}
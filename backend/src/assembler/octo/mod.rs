@@ -0,0 +1,955 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::disasm::instruction::Instruction;
+use crate::engine::constants::{START_ADDRESS, XO_CHIP_MEMORY_SIZE};
+
+use super::errors::AssemblerError;
+use super::{Assembly, Label, Monitor};
+use calc::Calc;
+use lexer::{Token, tokenize};
+
+pub mod calc;
+pub mod lexer;
+
+#[derive(Clone, Copy)]
+enum Reference {
+    Address,
+    Long,
+}
+
+struct Macro {
+    arguments: Vec<String>,
+    body: Vec<Token>,
+    calls: usize,
+}
+
+struct Loop {
+    start: usize,
+    exits: Vec<usize>,
+    token: Token,
+}
+
+fn unexpected(token: &Token) -> AssemblerError {
+    AssemblerError::UnexpectedToken {
+        token: token.text.clone(),
+        line: token.line,
+        column: token.column,
+    }
+}
+
+fn unbalanced(token: &Token) -> AssemblerError {
+    AssemblerError::UnbalancedBlock {
+        token: token.text.clone(),
+        line: token.line,
+        column: token.column,
+    }
+}
+
+fn out_of_range(value: i64, token: &Token) -> AssemblerError {
+    AssemblerError::ValueOutOfRange {
+        value: value as i32,
+        line: token.line,
+        column: token.column,
+    }
+}
+
+struct Compiler {
+    tokens: VecDeque<Token>,
+    last: Token,
+    rom: Vec<u8>,
+    here: usize,
+    constants: HashMap<String, f64>,
+    labels: HashMap<String, u16>,
+    aliases: HashMap<String, u8>,
+    macros: HashMap<String, Macro>,
+    references: Vec<(String, usize, Reference, Token)>,
+    loops: Vec<Loop>,
+    branches: Vec<(usize, Token)>,
+    breakpoints: Vec<Label>,
+    monitors: Vec<(Token, Token)>,
+}
+
+impl Compiler {
+    fn new(tokens: Vec<Token>) -> Self {
+        let last = tokens.last().cloned().unwrap_or(Token {
+            text: String::new(),
+            line: 1,
+            column: 1,
+        });
+
+        Self {
+            tokens: tokens.into(),
+            last,
+            rom: Vec::new(),
+            here: START_ADDRESS,
+            constants: HashMap::new(),
+            labels: HashMap::new(),
+            aliases: HashMap::new(),
+            macros: HashMap::new(),
+            references: Vec::new(),
+            loops: Vec::new(),
+            branches: Vec::new(),
+            breakpoints: Vec::new(),
+            monitors: Vec::new(),
+        }
+    }
+
+    fn next(&mut self) -> Result<Token, AssemblerError> {
+        let token = self
+            .tokens
+            .pop_front()
+            .ok_or(AssemblerError::UnexpectedEnd {
+                line: self.last.line,
+                column: self.last.column,
+            })?;
+
+        self.last = token.clone();
+
+        Ok(token)
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.front().map(|token| token.text.as_str())
+    }
+
+    fn expect(&mut self, text: &str) -> Result<Token, AssemblerError> {
+        let token = self.next()?;
+
+        if token.text != text {
+            Err(unexpected(&token))?;
+        }
+
+        Ok(token)
+    }
+
+    fn register(&self, token: &Token) -> Option<u8> {
+        match token.text.strip_prefix(['v', 'V']) {
+            Some(register) if register.len() == 1 => u8::from_str_radix(register, 16).ok(),
+
+            _ => self.aliases.get(&token.text).copied(),
+        }
+    }
+
+    fn next_register(&mut self) -> Result<u8, AssemblerError> {
+        let token = self.next()?;
+
+        self.register(&token).ok_or(unexpected(&token))
+    }
+
+    fn value(&self, token: &Token) -> Option<i64> {
+        token
+            .number()
+            .or_else(|| {
+                self.constants
+                    .get(&token.text)
+                    .map(|value| value.floor() as i64)
+            })
+            .or_else(|| self.labels.get(&token.text).map(|address| *address as i64))
+    }
+
+    fn number(&mut self, min: i64, max: i64) -> Result<i64, AssemblerError> {
+        let token = self.next()?;
+
+        let value = self.value(&token).ok_or(AssemblerError::UndefinedSymbol {
+            symbol: token.text.clone(),
+            line: token.line,
+            column: token.column,
+        })?;
+
+        if value < min || value > max {
+            Err(out_of_range(value, &token))?;
+        }
+
+        Ok(value)
+    }
+
+    fn byte(&mut self) -> Result<u8, AssemblerError> {
+        Ok(self.number(-0x80, 0xFF)? as u8)
+    }
+
+    fn nibble(&mut self) -> Result<u8, AssemblerError> {
+        Ok(self.number(0, 0xF)? as u8)
+    }
+
+    // Labels can be used before they are defined, so unknown names are patched once compiled
+    fn address(&mut self, token: &Token, reference: Reference) -> Result<u16, AssemblerError> {
+        let max = match reference {
+            Reference::Address => 0xFFF,
+            Reference::Long => 0xFFFF,
+        };
+
+        match self.value(token) {
+            Some(value) if value < 0 || value > max => Err(out_of_range(value, token)),
+            Some(value) => Ok(value as u16),
+            None if self.register(token).is_some() => Err(unexpected(token)),
+            None => {
+                self.references
+                    .push((token.text.clone(), self.here, reference, token.clone()));
+
+                Ok(0)
+            },
+        }
+    }
+
+    fn calc(&mut self) -> Result<f64, AssemblerError> {
+        self.expect("{")?;
+
+        let mut tokens = Vec::new();
+
+        loop {
+            let token = self.next()?;
+
+            if token.text == "}" {
+                break;
+            }
+
+            tokens.push(token);
+        }
+
+        let name = |token: &Token| {
+            if token.text == "HERE" {
+                return Ok(self.here as f64);
+            }
+
+            self.constants
+                .get(&token.text)
+                .copied()
+                .or_else(|| self.labels.get(&token.text).map(|address| *address as f64))
+                .or_else(|| self.register(token).map(|register| register as f64))
+                .ok_or(AssemblerError::UndefinedSymbol {
+                    symbol: token.text.clone(),
+                    line: token.line,
+                    column: token.column,
+                })
+        };
+        let memory = |address: f64| {
+            (address as usize)
+                .checked_sub(START_ADDRESS)
+                .and_then(|offset| self.rom.get(offset))
+                .map_or(0.0, |byte| *byte as f64)
+        };
+
+        Calc::evaluate(&tokens, &self.last, &name, &memory)
+    }
+
+    fn emit(&mut self, bytes: &[u8], token: &Token) -> Result<(), AssemblerError> {
+        if self.here + bytes.len() > XO_CHIP_MEMORY_SIZE {
+            Err(AssemblerError::AddressOutOfRange {
+                address: self.here as i32,
+                line: token.line,
+                column: token.column,
+            })?;
+        }
+
+        let offset = self.here - START_ADDRESS;
+
+        if self.rom.len() < offset + bytes.len() {
+            self.rom.resize(offset + bytes.len(), 0);
+        }
+
+        self.rom[offset..offset + bytes.len()].copy_from_slice(bytes);
+        self.here += bytes.len();
+
+        Ok(())
+    }
+
+    fn instruction(
+        &mut self,
+        instruction: Instruction,
+        token: &Token,
+    ) -> Result<(), AssemblerError> {
+        self.emit(&instruction.encode(), token)
+    }
+
+    fn patch(
+        &mut self,
+        at: usize,
+        reference: Reference,
+        address: usize,
+        token: &Token,
+    ) -> Result<(), AssemblerError> {
+        let offset = at - START_ADDRESS;
+
+        match reference {
+            Reference::Address if address > 0xFFF => Err(out_of_range(address as i64, token))?,
+            Reference::Address => {
+                self.rom[offset] = (self.rom[offset] & 0xF0) | (address >> 8) as u8;
+                self.rom[offset + 1] = address as u8;
+            },
+            Reference::Long => {
+                self.rom[offset + 2] = (address >> 8) as u8;
+                self.rom[offset + 3] = address as u8;
+            },
+        }
+
+        Ok(())
+    }
+
+    fn define(&mut self, name: &Token) -> Result<(), AssemblerError> {
+        if self.labels.contains_key(&name.text) || self.constants.contains_key(&name.text) {
+            Err(AssemblerError::DuplicateSymbol {
+                symbol: name.text.clone(),
+                line: name.line,
+                column: name.column,
+            })?;
+        }
+
+        if name.number().is_some() || self.register(name).is_some() {
+            Err(unexpected(name))?;
+        }
+
+        Ok(())
+    }
+
+    fn label(&mut self, name: &Token) -> Result<(), AssemblerError> {
+        self.define(name)?;
+
+        // The jump to main is dropped when main is the first thing in the program
+        if name.text == "main" && self.here == START_ADDRESS + 2 && self.rom.len() == 2 {
+            self.rom.clear();
+            self.here = START_ADDRESS;
+        }
+
+        self.labels.insert(name.text.clone(), self.here as u16);
+
+        Ok(())
+    }
+
+    fn define_macro(&mut self) -> Result<(), AssemblerError> {
+        let name = self.next()?;
+        self.define(&name)?;
+
+        let mut arguments = Vec::new();
+
+        loop {
+            let token = self.next()?;
+
+            if token.text == "{" {
+                break;
+            }
+
+            arguments.push(token.text);
+        }
+
+        let mut body = Vec::new();
+        let mut depth = 0;
+
+        loop {
+            let token = self.next()?;
+
+            match token.text.as_str() {
+                "{" => depth += 1,
+                "}" if depth == 0 => break,
+                "}" => depth -= 1,
+                _ => {},
+            }
+
+            body.push(token);
+        }
+
+        self.macros.insert(
+            name.text,
+            Macro {
+                arguments,
+                body,
+                calls: 0,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn expand(&mut self, name: &str) -> Result<(), AssemblerError> {
+        let count = self.macros[name].arguments.len();
+        let values = (0..count)
+            .map(|_| self.next())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let definition = self.macros.get_mut(name).unwrap();
+        definition.calls += 1;
+
+        let calls = definition.calls - 1;
+
+        for token in definition.body.iter().rev() {
+            let token = match definition
+                .arguments
+                .iter()
+                .position(|argument| *argument == token.text)
+            {
+                Some(index) => values[index].clone(),
+                None if token.text == "CALLS" => Token {
+                    text: calls.to_string(),
+                    ..token.clone()
+                },
+                None => token.clone(),
+            };
+
+            self.tokens.push_front(token);
+        }
+
+        Ok(())
+    }
+
+    // Returns the instructions that skip the next one when the condition is false and when it is
+    // true
+    fn condition(&mut self) -> Result<(Instruction, Instruction), AssemblerError> {
+        let x = self.next_register()?;
+        let operator = self.next()?;
+
+        let (skip_false, skip_true) = match operator.text.as_str() {
+            "key" => (Instruction::SkipNotKey(x), Instruction::SkipKey(x)),
+            "-key" => (Instruction::SkipKey(x), Instruction::SkipNotKey(x)),
+            "==" | "!=" => {
+                let right = self.next()?;
+
+                let (skip_false, skip_true) = match self.register(&right) {
+                    Some(y) => (
+                        Instruction::SkipNotEqual(x, y),
+                        Instruction::SkipEqual(x, y),
+                    ),
+                    None => {
+                        self.tokens.push_front(right);
+                        let nn = self.byte()?;
+
+                        (
+                            Instruction::SkipNotEqualByte(x, nn),
+                            Instruction::SkipEqualByte(x, nn),
+                        )
+                    },
+                };
+
+                if operator.text == "==" {
+                    (skip_false, skip_true)
+                } else {
+                    (skip_true, skip_false)
+                }
+            },
+            // Comparisons subtract into a temporary register and test the resulting flag
+            "<" | ">" | "<=" | ">=" => {
+                let right = self.next()?;
+                let temp = self.aliases.get("compare-temp").copied().unwrap_or(0xF);
+
+                let y = self.register(&right);
+                let load = match y {
+                    Some(y) => Instruction::Move(temp, y),
+                    None => {
+                        self.tokens.push_front(right);
+                        Instruction::LoadByte(temp, self.byte()?)
+                    },
+                };
+
+                let (first, second) = match (operator.text.as_str(), y) {
+                    ("<" | ">=", Some(y)) => {
+                        (Instruction::Move(temp, x), Instruction::Sub(temp, y))
+                    },
+                    ("<" | ">=", None) => (load, Instruction::SubNegated(temp, x)),
+
+                    _ => (load, Instruction::Sub(temp, x)),
+                };
+
+                self.instruction(first, &operator)?;
+                self.instruction(second, &operator)?;
+
+                let flag = matches!(operator.text.as_str(), "<=" | ">=") as u8;
+
+                // The flag is always left in VF, whichever register holds the difference
+                (
+                    Instruction::SkipNotEqualByte(0xF, flag),
+                    Instruction::SkipEqualByte(0xF, flag),
+                )
+            },
+
+            _ => Err(unexpected(&operator))?,
+        };
+
+        Ok((skip_false, skip_true))
+    }
+
+    fn register_statement(&mut self, x: u8) -> Result<(), AssemblerError> {
+        let operator = self.next()?;
+        let right = self.next()?;
+        let y = self.register(&right);
+
+        let instruction = match (operator.text.as_str(), y) {
+            (":=", _) if right.text == "random" => Instruction::Random(x, self.byte()?),
+            (":=", _) if right.text == "key" => Instruction::WaitKey(x),
+            (":=", _) if right.text == "delay" => Instruction::LoadDelay(x),
+            (":=", Some(y)) => Instruction::Move(x, y),
+            ("+=", Some(y)) => Instruction::Add(x, y),
+            ("-=", Some(y)) => Instruction::Sub(x, y),
+            ("=-", Some(y)) => Instruction::SubNegated(x, y),
+            ("|=", Some(y)) => Instruction::Or(x, y),
+            ("&=", Some(y)) => Instruction::And(x, y),
+            ("^=", Some(y)) => Instruction::Xor(x, y),
+            (">>=", Some(y)) => Instruction::ShiftRight(x, y),
+            ("<<=", Some(y)) => Instruction::ShiftLeft(x, y),
+            (":=" | "+=" | "-=", None) => {
+                self.tokens.push_front(right);
+                let nn = self.byte()?;
+
+                match operator.text.as_str() {
+                    ":=" => Instruction::LoadByte(x, nn),
+                    "+=" => Instruction::AddByte(x, nn),
+
+                    _ => Instruction::AddByte(x, nn.wrapping_neg()),
+                }
+            },
+
+            _ => Err(unexpected(&operator))?,
+        };
+
+        self.instruction(instruction, &operator)
+    }
+
+    fn index_statement(&mut self, token: &Token) -> Result<(), AssemblerError> {
+        let operator = self.next()?;
+
+        if operator.text == "+=" {
+            let x = self.next_register()?;
+
+            return self.instruction(Instruction::AddIndex(x), token);
+        }
+
+        if operator.text != ":=" {
+            Err(unexpected(&operator))?;
+        }
+
+        let right = self.next()?;
+
+        let instruction = match right.text.as_str() {
+            "hex" => Instruction::LoadFont(self.next_register()?),
+            "bighex" => Instruction::LoadLargeFont(self.next_register()?),
+            "long" => {
+                let address = self.next()?;
+
+                Instruction::LoadLongIndex(self.address(&address, Reference::Long)?)
+            },
+
+            _ => Instruction::LoadIndex(self.address(&right, Reference::Address)?),
+        };
+
+        self.instruction(instruction, token)
+    }
+
+    fn statement(&mut self) -> Result<(), AssemblerError> {
+        let token = self.next()?;
+
+        if let Some(x) = self.register(&token) {
+            return self.register_statement(x);
+        }
+
+        let instruction = match token.text.as_str() {
+            ":" => {
+                let name = self.next()?;
+
+                return self.label(&name);
+            },
+            ":alias" => {
+                let name = self.next()?;
+                self.define(&name)?;
+
+                let x = self.next_register()?;
+                self.aliases.insert(name.text, x);
+
+                return Ok(());
+            },
+            ":const" => {
+                let name = self.next()?;
+                self.define(&name)?;
+
+                let value = self.number(i64::MIN, i64::MAX)?;
+                self.constants.insert(name.text, value as f64);
+
+                return Ok(());
+            },
+            ":calc" => {
+                let name = self.next()?;
+
+                if !self.constants.contains_key(&name.text) {
+                    self.define(&name)?;
+                }
+
+                let value = self.calc()?;
+                self.constants.insert(name.text, value);
+
+                return Ok(());
+            },
+            ":macro" => return self.define_macro(),
+            ":byte" => {
+                let byte = if self.peek() == Some("{") {
+                    self.calc()?.floor() as i64 as u8
+                } else {
+                    self.byte()?
+                };
+
+                return self.emit(&[byte], &token);
+            },
+            ":org" => {
+                let address = self.number(START_ADDRESS as i64, XO_CHIP_MEMORY_SIZE as i64)?;
+                self.here = address as usize;
+
+                return Ok(());
+            },
+            ":assert" => {
+                let message = match self.tokens.front().and_then(|token| token.string()) {
+                    Some(message) => {
+                        let message = message.to_string();
+                        self.next()?;
+
+                        message
+                    },
+                    None => "expression is zero".to_string(),
+                };
+
+                if self.calc()? == 0.0 {
+                    Err(AssemblerError::AssertionFailed {
+                        message,
+                        line: token.line,
+                        column: token.column,
+                    })?;
+                }
+
+                return Ok(());
+            },
+            ":breakpoint" => {
+                let name = self.next()?;
+
+                self.breakpoints.push(Label {
+                    name: name.text,
+                    address: self.here as u16,
+                });
+
+                return Ok(());
+            },
+            ":monitor" => {
+                let target = self.next()?;
+                let length = self.next()?;
+
+                self.monitors.push((target, length));
+
+                return Ok(());
+            },
+            ":call" => {
+                let address = self.next()?;
+
+                Instruction::Call(self.address(&address, Reference::Address)?)
+            },
+            ";" | "return" => Instruction::Return,
+            "clear" => Instruction::Clear,
+            "hires" => Instruction::HighResolution,
+            "lores" => Instruction::LowResolution,
+            "exit" => Instruction::Exit,
+            "scroll-down" => Instruction::ScrollDown(self.nibble()?),
+            "scroll-up" => Instruction::ScrollUp(self.nibble()?),
+            "scroll-left" => Instruction::ScrollLeft,
+            "scroll-right" => Instruction::ScrollRight,
+            "audio" => Instruction::Audio,
+            "plane" => Instruction::Plane(self.nibble()?),
+            "bcd" => Instruction::Bcd(self.next_register()?),
+            "saveflags" => Instruction::StoreFlags(self.next_register()?),
+            "loadflags" => Instruction::LoadFlags(self.next_register()?),
+            "save" | "load" => {
+                let x = self.next_register()?;
+
+                match self.peek() {
+                    Some("-") => {
+                        self.next()?;
+                        let y = self.next_register()?;
+
+                        match token.text.as_str() {
+                            "save" => Instruction::StoreRange(x, y),
+                            _ => Instruction::LoadRange(x, y),
+                        }
+                    },
+                    _ if token.text == "save" => Instruction::StoreRegisters(x),
+
+                    _ => Instruction::LoadRegisters(x),
+                }
+            },
+            "sprite" => {
+                let x = self.next_register()?;
+                let y = self.next_register()?;
+
+                Instruction::Draw(x, y, self.nibble()?)
+            },
+            "jump" => {
+                let address = self.next()?;
+
+                Instruction::Jump(self.address(&address, Reference::Address)?)
+            },
+            "jump0" => {
+                let address = self.next()?;
+
+                Instruction::JumpOffset(self.address(&address, Reference::Address)?)
+            },
+            "delay" | "buzzer" | "pitch" => {
+                self.expect(":=")?;
+                let x = self.next_register()?;
+
+                match token.text.as_str() {
+                    "delay" => Instruction::SetDelay(x),
+                    "buzzer" => Instruction::SetSound(x),
+
+                    _ => Instruction::Pitch(x),
+                }
+            },
+            "i" => return self.index_statement(&token),
+            "if" => {
+                let (skip_false, skip_true) = self.condition()?;
+                let mode = self.next()?;
+
+                match mode.text.as_str() {
+                    "then" => skip_false,
+                    "begin" => {
+                        self.instruction(skip_true, &mode)?;
+                        self.branches.push((self.here, token.clone()));
+
+                        Instruction::Jump(0)
+                    },
+
+                    _ => Err(unexpected(&mode))?,
+                }
+            },
+            "else" => {
+                let (jump, _) = self.branches.pop().ok_or(unbalanced(&token))?;
+
+                self.branches.push((self.here, token.clone()));
+                self.instruction(Instruction::Jump(0), &token)?;
+
+                return self.patch(jump, Reference::Address, self.here, &token);
+            },
+            "end" => {
+                let (jump, _) = self.branches.pop().ok_or(unbalanced(&token))?;
+
+                return self.patch(jump, Reference::Address, self.here, &token);
+            },
+            "loop" => {
+                self.loops.push(Loop {
+                    start: self.here,
+                    exits: Vec::new(),
+                    token: token.clone(),
+                });
+
+                return Ok(());
+            },
+            "while" => {
+                if self.loops.is_empty() {
+                    Err(unbalanced(&token))?;
+                }
+
+                let (_, skip_true) = self.condition()?;
+                self.instruction(skip_true, &token)?;
+
+                let exit = self.here;
+                self.loops.last_mut().unwrap().exits.push(exit);
+
+                Instruction::Jump(0)
+            },
+            "again" => {
+                let Loop { start, exits, .. } = self.loops.pop().ok_or(unbalanced(&token))?;
+
+                if start > 0xFFF {
+                    Err(out_of_range(start as i64, &token))?;
+                }
+
+                self.instruction(Instruction::Jump(start as u16), &token)?;
+
+                for exit in exits {
+                    self.patch(exit, Reference::Address, self.here, &token)?;
+                }
+
+                return Ok(());
+            },
+            name if self.macros.contains_key(name) => return self.expand(name),
+            // Numbers are emitted as data and any other name is a subroutine call
+            _ => match token.number().or_else(|| {
+                self.constants
+                    .get(&token.text)
+                    .map(|value| value.floor() as i64)
+            }) {
+                Some(value) if !(-0x80..=0xFF).contains(&value) => {
+                    Err(out_of_range(value, &token))?
+                },
+                Some(value) => return self.emit(&[value as u8], &token),
+                None => Instruction::Call(self.address(&token, Reference::Address)?),
+            },
+        };
+
+        self.instruction(instruction, &token)
+    }
+
+    fn finish(mut self) -> Result<Assembly, AssemblerError> {
+        if let Some((_, token)) = self.branches.first() {
+            Err(unbalanced(token))?;
+        }
+
+        if let Some(Loop { token, .. }) = self.loops.first() {
+            Err(unbalanced(token))?;
+        }
+
+        let main = *self
+            .labels
+            .get("main")
+            .ok_or(AssemblerError::UndefinedSymbol {
+                symbol: "main".to_string(),
+                line: self.last.line,
+                column: self.last.column,
+            })?;
+
+        if main != START_ADDRESS as u16 {
+            let jump = Instruction::Jump(main & 0x0FFF).encode();
+            self.rom[..2].copy_from_slice(&jump);
+        }
+
+        for (name, at, reference, token) in std::mem::take(&mut self.references) {
+            let address = *self
+                .labels
+                .get(&name)
+                .ok_or(AssemblerError::UndefinedSymbol {
+                    symbol: name,
+                    line: token.line,
+                    column: token.column,
+                })?;
+
+            self.patch(at, reference, address as usize, &token)?;
+        }
+
+        let mut monitors = Vec::new();
+
+        for (target, length) in &self.monitors {
+            let address = self.value(target).ok_or(AssemblerError::UndefinedSymbol {
+                symbol: target.text.clone(),
+                line: target.line,
+                column: target.column,
+            })?;
+
+            let (length, format) = match length.string() {
+                Some(format) => (format.matches('%').count(), Some(format.to_string())),
+                None => (self.value(length).ok_or(unexpected(length))? as usize, None),
+            };
+
+            monitors.push(Monitor {
+                name: target.text.clone(),
+                address: address as u16,
+                length: length as u16,
+                format,
+            });
+        }
+
+        let mut labels: Vec<Label> = self
+            .labels
+            .into_iter()
+            .map(|(name, address)| Label { name, address })
+            .collect();
+        labels.sort_by(|a, b| a.address.cmp(&b.address).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(Assembly {
+            rom: self.rom,
+            labels,
+            breakpoints: self.breakpoints,
+            monitors,
+        })
+    }
+}
+
+pub fn compile(source: &str) -> Result<Assembly, AssemblerError> {
+    let mut compiler = Compiler::new(tokenize(source)?);
+
+    // Reserves space for the jump to main
+    let start = compiler.last.clone();
+    compiler.emit(&[0x00, 0x00], &start)?;
+
+    while !compiler.tokens.is_empty() {
+        compiler.statement()?;
+    }
+
+    compiler.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compile;
+    use crate::engine::Engine;
+    use crate::engine::quirks::{Platform, Quirks};
+
+    type Comparison = fn(u8, u8) -> bool;
+
+    fn run(source: &str, cycles: usize) -> Engine {
+        let assembly = compile(source).unwrap();
+        let mut engine = Engine::new(Platform::Chip48, Quirks::new(Platform::Chip48));
+        engine.load_rom(&assembly.rom).unwrap();
+
+        for _ in 0..cycles {
+            engine.execute_cycle().unwrap();
+        }
+
+        engine
+    }
+
+    #[test]
+    fn comparisons() {
+        let values = [0u8, 1, 5, 127, 128, 200, 255];
+        let operators: [(&str, Comparison); 4] = [
+            ("<", |a, b| a < b),
+            (">", |a, b| a > b),
+            ("<=", |a, b| a <= b),
+            (">=", |a, b| a >= b),
+        ];
+
+        for (operator, expected) in operators {
+            for a in values {
+                for b in values {
+                    let source = format!(
+                        ": main
+                            v0 := {a} v1 := {b} v2 := 0 v3 := 0
+                            if v0 {operator} v1 then v2 := 1
+                            if v0 {operator} {b} then v3 := 1
+                            loop again"
+                    );
+                    let engine = run(&source, 20);
+                    let expected = expected(a, b) as u8;
+
+                    assert_eq!(
+                        engine.get_register(2),
+                        expected,
+                        "v0 {operator} v1 ({a}, {b})"
+                    );
+                    assert_eq!(engine.get_register(3), expected, "v0 {operator} {b} ({a})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn comparison_with_alias() {
+        let source = ":alias compare-temp v9
+            : main
+                v0 := 3 v1 := 7 v2 := 0
+                if v0 < v1 then v2 := 1
+                loop again";
+
+        assert_eq!(run(source, 20).get_register(2), 1);
+    }
+
+    #[test]
+    fn control_flow() {
+        let source = ": main
+                v0 := 0 v1 := 0 v2 := 0
+                loop
+                    v0 += 1
+                    if v0 == 3 begin v1 := 1 else v2 += 1 end
+                    while v0 != 5
+                again
+                add-one
+                loop again
+            : add-one
+                v3 := 1
+                return";
+        let engine = run(source, 100);
+
+        assert_eq!(engine.get_register(0), 5);
+        assert_eq!(engine.get_register(1), 1);
+        assert_eq!(engine.get_register(2), 4);
+        assert_eq!(engine.get_register(3), 1);
+    }
+}
@@ -0,0 +1,11 @@
+pub const FRAME_RATE: f32 = 60.0;
+pub const MAX_QUEUED_FRAMES: usize = 8;
+
+pub const DEFAULT_FREQUENCY: f32 = 440.0;
+pub const DEFAULT_VOLUME: f32 = 0.25;
+
+// XO-CHIP plays the 128 bits of the pattern at 4000 * 2 ^ ((pitch - 64) / 48) bits per second
+pub const PATTERN_BITS: f32 = 128.0;
+pub const PATTERN_RATE: f32 = 4000.0;
+pub const PITCH_OFFSET: f32 = 64.0;
+pub const PITCH_OCTAVE: f32 = 48.0;
@@ -0,0 +1,195 @@
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::engine::constants::AUDIO_PATTERN_SIZE;
+
+use constants::{
+    DEFAULT_FREQUENCY, DEFAULT_VOLUME, FRAME_RATE, MAX_QUEUED_FRAMES, PATTERN_BITS, PATTERN_RATE,
+    PITCH_OCTAVE, PITCH_OFFSET,
+};
+
+pub mod constants;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Waveform {
+    #[default]
+    Square,
+    Triangle,
+    Sawtooth,
+    Sine,
+}
+
+impl Waveform {
+    // Returns the value of the waveform between -1 and 1 at a phase between 0 and 1
+    fn sample(&self, phase: f32) -> f32 {
+        match self {
+            Waveform::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            },
+            Waveform::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            Waveform::Sawtooth => 2.0 * phase - 1.0,
+            Waveform::Sine => (phase * TAU).sin(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Tone {
+    Silence,
+    Wave,
+    Pattern([u8; AUDIO_PATTERN_SIZE], u8),
+}
+
+// Turns the sound timer of every frame into samples, so the sound starts and stops at the same
+// point of the output no matter how many samples the host asks for at once
+pub struct Audio {
+    frequency: f32,
+    volume: f32,
+    waveform: Waveform,
+    frames: VecDeque<Tone>,
+    tone: Tone,
+    remaining: f32,
+    phase: f32,
+}
+
+impl Audio {
+    pub fn new() -> Self {
+        Self {
+            frequency: DEFAULT_FREQUENCY,
+            volume: DEFAULT_VOLUME,
+            waveform: Waveform::default(),
+            frames: VecDeque::new(),
+            tone: Tone::Silence,
+            remaining: 0.0,
+            phase: 0.0,
+        }
+    }
+
+    pub fn get_frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency.max(0.0);
+    }
+
+    pub fn get_volume(&self) -> f32 {
+        self.volume
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn get_waveform(&self) -> Waveform {
+        self.waveform
+    }
+
+    pub fn set_waveform(&mut self, waveform: Waveform) {
+        self.waveform = waveform;
+    }
+
+    // Queues the sound of a frame, dropping the oldest ones when the host falls behind
+    pub fn push_frame(&mut self, active: bool, pattern: Option<(&[u8; AUDIO_PATTERN_SIZE], u8)>) {
+        let tone = match (active, pattern) {
+            (false, _) => Tone::Silence,
+            (true, Some((pattern, pitch))) => Tone::Pattern(*pattern, pitch),
+            (true, None) => Tone::Wave,
+        };
+
+        if self.frames.len() == MAX_QUEUED_FRAMES {
+            self.frames.pop_front();
+        }
+
+        self.frames.push_back(tone);
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.tone = Tone::Silence;
+        self.remaining = 0.0;
+    }
+
+    pub fn fill(&mut self, buffer: &mut [f32], sample_rate: f32) {
+        if sample_rate <= 0.0 {
+            buffer.fill(0.0);
+            return;
+        }
+
+        let frame_samples = sample_rate / FRAME_RATE;
+
+        for sample in buffer.iter_mut() {
+            while self.remaining < 1.0 {
+                // Without queued frames the output stays silent until the emulator catches up
+                let Some(tone) = self.frames.pop_front() else {
+                    self.tone = Tone::Silence;
+                    self.remaining = 1.0;
+                    break;
+                };
+
+                // Waves and patterns measure the phase in different units, so it only carries
+                // over between frames of the same kind
+                if std::mem::discriminant(&self.tone) != std::mem::discriminant(&tone) {
+                    self.phase = 0.0;
+                }
+
+                self.tone = tone;
+                self.remaining += frame_samples;
+            }
+
+            *sample = self.next_sample(sample_rate);
+            self.remaining -= 1.0;
+        }
+    }
+
+    fn next_sample(&mut self, sample_rate: f32) -> f32 {
+        match self.tone {
+            Tone::Silence => 0.0,
+            Tone::Wave => {
+                let value = self.waveform.sample(self.phase);
+                self.phase = (self.phase + self.frequency / sample_rate).fract();
+
+                value * self.volume
+            },
+            Tone::Pattern(pattern, pitch) => {
+                let bit = self.phase as usize;
+                let value = if pattern[bit / 8] & (0x80 >> (bit % 8)) != 0 {
+                    1.0
+                } else {
+                    -1.0
+                };
+
+                let rate = PATTERN_RATE * 2f32.powf((pitch as f32 - PITCH_OFFSET) / PITCH_OCTAVE);
+                self.phase = (self.phase + rate / sample_rate) % PATTERN_BITS;
+
+                value * self.volume
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Audio, Waveform};
+
+    #[test]
+    fn pattern_then_wave() {
+        let mut audio = Audio::new();
+        audio.set_volume(1.0);
+        audio.set_waveform(Waveform::Triangle);
+        audio.push_frame(true, Some((&[0xF0; 16], 64)));
+        audio.push_frame(true, None);
+
+        let mut buffer = [0.0; 1600];
+        audio.fill(&mut buffer, 48000.0);
+
+        assert!(buffer.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+    }
+}
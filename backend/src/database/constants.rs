@@ -0,0 +1,26 @@
+use crate::engine::quirks::Platform;
+
+// Subset of the community CHIP-8 database covering the bundled ROMs
+pub const DATABASE: &str = include_str!("programs.json");
+
+// Platform identifiers of the database with the closest platform of the emulator
+pub const PLATFORMS: [(&str, Platform); 8] = [
+    ("originalChip8", Platform::Vip),
+    ("hybridVIP", Platform::Vip),
+    ("chip8x", Platform::Vip),
+    ("modernChip8", Platform::Chip48),
+    ("chip48", Platform::Chip48),
+    ("superchip1", Platform::Schip),
+    ("superchip", Platform::Schip),
+    ("xochip", Platform::XoChip),
+];
+
+// Controls of the database with the host keys and gamepad buttons bound to them
+pub const CONTROLS: [(&str, &[&str]); 6] = [
+    ("up", &["ArrowUp", "GamepadUp"]),
+    ("down", &["ArrowDown", "GamepadDown"]),
+    ("left", &["ArrowLeft", "GamepadLeft"]),
+    ("right", &["ArrowRight", "GamepadRight"]),
+    ("a", &["space", "GamepadA"]),
+    ("b", &["GamepadB"]),
+];
@@ -0,0 +1,57 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum DatabaseError {
+    UnexpectedEnd { position: usize },
+    UnexpectedCharacter { character: char, position: usize },
+    InvalidNumber { position: usize },
+    InvalidEscape { position: usize },
+    InvalidProgram { index: usize },
+}
+
+impl DatabaseError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DatabaseError::UnexpectedEnd { .. } => "DatabaseUnexpectedEnd",
+            DatabaseError::UnexpectedCharacter { .. } => "DatabaseUnexpectedCharacter",
+            DatabaseError::InvalidNumber { .. } => "DatabaseInvalidNumber",
+            DatabaseError::InvalidEscape { .. } => "DatabaseInvalidEscape",
+            DatabaseError::InvalidProgram { .. } => "DatabaseInvalidProgram",
+        }
+    }
+}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatabaseError::UnexpectedEnd { position } => {
+                write!(f, "Unexpected end of JSON at byte {}", position)
+            },
+            DatabaseError::UnexpectedCharacter {
+                character,
+                position,
+            } => {
+                write!(
+                    f,
+                    "Unexpected character {:?} at byte {}",
+                    character, position
+                )
+            },
+            DatabaseError::InvalidNumber { position } => {
+                write!(f, "Invalid number at byte {}", position)
+            },
+            DatabaseError::InvalidEscape { position } => {
+                write!(f, "Invalid escape sequence at byte {}", position)
+            },
+            DatabaseError::InvalidProgram { index } => {
+                write!(
+                    f,
+                    "Program {} is not an object with a title and ROMs",
+                    index
+                )
+            },
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {}
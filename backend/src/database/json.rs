@@ -0,0 +1,393 @@
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use super::errors::DatabaseError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    // Members keep the order of the document
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(text) => Some(text),
+
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(*number),
+
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(value) => Some(*value),
+
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Object(members) => Some(members),
+
+            _ => None,
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Result<Value, DatabaseError> {
+    let mut parser = Parser {
+        chars: text.char_indices().peekable(),
+        length: text.len(),
+    };
+
+    let value = parser.value()?;
+    parser.skip_whitespace();
+
+    match parser.chars.next() {
+        Some((position, character)) => Err(DatabaseError::UnexpectedCharacter {
+            character,
+            position,
+        }),
+        None => Ok(value),
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    length: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .next_if(|(_, character)| character.is_ascii_whitespace())
+            .is_some()
+        {}
+    }
+
+    fn next(&mut self) -> Result<(usize, char), DatabaseError> {
+        self.chars.next().ok_or(DatabaseError::UnexpectedEnd {
+            position: self.length,
+        })
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), DatabaseError> {
+        let (position, character) = self.next()?;
+
+        if character != expected {
+            Err(DatabaseError::UnexpectedCharacter {
+                character,
+                position,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Value, DatabaseError> {
+        self.skip_whitespace();
+
+        let (position, character) = *self.chars.peek().ok_or(DatabaseError::UnexpectedEnd {
+            position: self.length,
+        })?;
+
+        match character {
+            '{' => self.object(),
+            '[' => self.array(),
+            '"' => Ok(Value::String(self.string()?)),
+            't' => self.literal("true", Value::Bool(true)),
+            'f' => self.literal("false", Value::Bool(false)),
+            'n' => self.literal("null", Value::Null),
+            '-' | '0'..='9' => self.number(),
+
+            _ => Err(DatabaseError::UnexpectedCharacter {
+                character,
+                position,
+            }),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Value) -> Result<Value, DatabaseError> {
+        for expected in word.chars() {
+            self.expect(expected)?;
+        }
+
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Value, DatabaseError> {
+        let mut text = String::new();
+        let position = self
+            .chars
+            .peek()
+            .map_or(self.length, |(position, _)| *position);
+
+        while let Some((_, character)) = self
+            .chars
+            .next_if(|(_, character)| matches!(character, '-' | '+' | '.' | 'e' | 'E' | '0'..='9'))
+        {
+            text.push(character);
+        }
+
+        text.parse()
+            .map(Value::Number)
+            .map_err(|_| DatabaseError::InvalidNumber { position })
+    }
+
+    fn string(&mut self) -> Result<String, DatabaseError> {
+        let mut text = String::new();
+
+        self.expect('"')?;
+
+        loop {
+            match self.next()? {
+                (_, '"') => return Ok(text),
+                (position, '\\') => text.push(self.escape(position)?),
+                (_, character) => text.push(character),
+            }
+        }
+    }
+
+    fn escape(&mut self, position: usize) -> Result<char, DatabaseError> {
+        let character = match self.next()?.1 {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let high = self.code_unit(position)?;
+
+                // Characters outside the basic plane are written as a pair of surrogates
+                let code = if (0xD800..0xDC00).contains(&high) {
+                    self.expect('\\')?;
+                    self.expect('u')?;
+                    let low = self.code_unit(position)?;
+
+                    if !(0xDC00..0xE000).contains(&low) {
+                        Err(DatabaseError::InvalidEscape { position })?;
+                    }
+
+                    0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    high
+                };
+
+                char::from_u32(code).ok_or(DatabaseError::InvalidEscape { position })?
+            },
+
+            _ => Err(DatabaseError::InvalidEscape { position })?,
+        };
+
+        Ok(character)
+    }
+
+    fn code_unit(&mut self, position: usize) -> Result<u32, DatabaseError> {
+        let mut code = 0;
+
+        for _ in 0..4 {
+            let digit = self.next()?.1.to_digit(16);
+            code = code * 16 + digit.ok_or(DatabaseError::InvalidEscape { position })?;
+        }
+
+        Ok(code)
+    }
+
+    fn array(&mut self) -> Result<Value, DatabaseError> {
+        let mut values = Vec::new();
+
+        self.expect('[')?;
+        self.skip_whitespace();
+
+        if self
+            .chars
+            .next_if(|(_, character)| *character == ']')
+            .is_some()
+        {
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+
+            match self.next()? {
+                (_, ',') => continue,
+                (_, ']') => return Ok(Value::Array(values)),
+                (position, character) => Err(DatabaseError::UnexpectedCharacter {
+                    character,
+                    position,
+                })?,
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, DatabaseError> {
+        let mut members = Vec::new();
+
+        self.expect('{')?;
+        self.skip_whitespace();
+
+        if self
+            .chars
+            .next_if(|(_, character)| *character == '}')
+            .is_some()
+        {
+            return Ok(Value::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let name = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            members.push((name, self.value()?));
+            self.skip_whitespace();
+
+            match self.next()? {
+                (_, ',') => continue,
+                (_, '}') => return Ok(Value::Object(members)),
+                (position, character) => Err(DatabaseError::UnexpectedCharacter {
+                    character,
+                    position,
+                })?,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Value, parse};
+    use crate::database::errors::DatabaseError;
+
+    #[test]
+    fn values() {
+        let value =
+            parse(r#" { "a": [1, -2.5, 3e2, true, false, null], "b": {}, "c": [] } "#).unwrap();
+
+        assert_eq!(
+            value.get("a").and_then(Value::as_array).unwrap(),
+            [
+                Value::Number(1.0),
+                Value::Number(-2.5),
+                Value::Number(300.0),
+                Value::Bool(true),
+                Value::Bool(false),
+                Value::Null,
+            ]
+        );
+        assert_eq!(value.get("b"), Some(&Value::Object(Vec::new())));
+        assert_eq!(value.get("c"), Some(&Value::Array(Vec::new())));
+        assert_eq!(value.get("d"), None);
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        // The first member wins, like `get` on any other object
+        let value = parse(r#"{"a": 1, "a": 2}"#).unwrap();
+
+        assert_eq!(value.get("a"), Some(&Value::Number(1.0)));
+    }
+
+    #[test]
+    fn escapes() {
+        let value = parse(r#""\"\\\/\b\f\n\r\t é 😀 é""#).unwrap();
+
+        assert_eq!(value.as_str(), Some("\"\\/\u{8}\u{c}\n\r\t é 😀 é"));
+    }
+
+    #[test]
+    fn invalid_escapes() {
+        for text in [
+            r#""\x""#,
+            r#""\u12""#,
+            r#""\u12G4""#,
+            r#""\ud800\u0000""#,
+            r#""\ud800\ud800""#,
+            r#""\udc00""#,
+        ] {
+            assert!(
+                matches!(parse(text), Err(DatabaseError::InvalidEscape { .. })),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            parse(""),
+            Err(DatabaseError::UnexpectedEnd { position: 0 })
+        ));
+        assert!(matches!(
+            parse("[1, 2"),
+            Err(DatabaseError::UnexpectedEnd { .. })
+        ));
+        assert!(matches!(
+            parse("\"abc"),
+            Err(DatabaseError::UnexpectedEnd { .. })
+        ));
+        assert!(matches!(
+            parse("[1, ]"),
+            Err(DatabaseError::UnexpectedCharacter {
+                character: ']',
+                position: 4
+            })
+        ));
+        assert!(matches!(
+            parse("{\"a\" 1}"),
+            Err(DatabaseError::UnexpectedCharacter { character: '1', .. })
+        ));
+        assert!(matches!(
+            parse("[] x"),
+            Err(DatabaseError::UnexpectedCharacter { character: 'x', .. })
+        ));
+        assert!(matches!(
+            parse("tru"),
+            Err(DatabaseError::UnexpectedEnd { .. })
+        ));
+        assert!(matches!(
+            parse("1-2"),
+            Err(DatabaseError::InvalidNumber { position: 0 })
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::engine::quirks::{Platform, Quirks};
+use crate::input::constants::KEY_COUNT;
+
+use constants::{CONTROLS, DATABASE, PLATFORMS};
+use errors::DatabaseError;
+use json::Value;
+
+pub mod constants;
+pub mod errors;
+pub mod json;
+pub mod sha1;
+
+// Recommended configuration of a ROM, taken from the database
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Debug)]
+pub struct RomInfo {
+    pub hash: String,
+    pub title: String,
+    pub author: Option<String>,
+    pub platform: Option<Platform>,
+    pub quirks: Option<Quirks>,
+    pub tick_rate: Option<u32>,
+    // Colors as 0xRRGGBB in the order of the palette
+    pub colors: Vec<u32>,
+    // Key bindings in the format of `export_key_bindings`
+    pub bindings: String,
+}
+
+// ROMs of the database indexed by the SHA-1 of their contents
+pub struct Database {
+    roms: HashMap<String, RomInfo>,
+}
+
+impl Database {
+    pub fn new() -> Self {
+        let mut database = Self {
+            roms: HashMap::new(),
+        };
+
+        database
+            .merge(DATABASE)
+            .expect("the embedded database is valid");
+
+        database
+    }
+
+    // Adds the programs of a document with the format of `programs.json` in the community
+    // database, replacing ROMs that are already known, and returns the number of ROMs that were
+    // not known before
+    pub fn merge(&mut self, text: &str) -> Result<usize, DatabaseError> {
+        let document = json::parse(text)?;
+        let mut roms = Vec::new();
+
+        for (index, program) in document.as_array().unwrap_or_default().iter().enumerate() {
+            let title = program.get("title").and_then(Value::as_str);
+            let entries = program.get("roms").and_then(Value::as_object);
+
+            let (Some(title), Some(entries)) = (title, entries) else {
+                Err(DatabaseError::InvalidProgram { index })?
+            };
+
+            let author = program
+                .get("authors")
+                .and_then(Value::as_array)
+                .map(|authors| {
+                    let names: Vec<&str> = authors.iter().filter_map(Value::as_str).collect();
+                    names.join(", ")
+                })
+                .filter(|author| !author.is_empty());
+
+            for (hash, rom) in entries {
+                roms.push(RomInfo {
+                    hash: hash.to_lowercase(),
+                    title: title.to_string(),
+                    author: author.clone(),
+                    ..parse_rom(rom)
+                });
+            }
+        }
+
+        let mut count = 0;
+
+        for rom in roms {
+            if self.roms.insert(rom.hash.clone(), rom).is_none() {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    pub fn lookup(&self, hash: &str) -> Option<&RomInfo> {
+        self.roms.get(&hash.to_lowercase())
+    }
+}
+
+fn parse_rom(rom: &Value) -> RomInfo {
+    // The first platform the emulator supports is the one recommended
+    let platform = rom
+        .get("platforms")
+        .and_then(Value::as_array)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Value::as_str)
+        .find_map(|name| PLATFORMS.iter().find(|(id, _)| *id == name));
+
+    let quirks = platform.map(|(id, platform)| {
+        let mut quirks = Quirks::new(*platform);
+
+        if let Some(overrides) = rom.get("quirkyPlatforms").and_then(|value| value.get(id)) {
+            apply_quirks(&mut quirks, overrides);
+        }
+
+        quirks
+    });
+
+    let colors = rom
+        .get("colors")
+        .and_then(|colors| colors.get("pixels"))
+        .and_then(Value::as_array)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|color| u32::from_str_radix(color.as_str()?.strip_prefix('#')?, 16).ok())
+        .collect();
+
+    let mut bindings = String::new();
+
+    for (control, key) in rom
+        .get("keys")
+        .and_then(Value::as_object)
+        .unwrap_or_default()
+    {
+        let key = key
+            .as_f64()
+            .filter(|key| *key >= 0.0 && *key < KEY_COUNT as f64);
+        let hosts = CONTROLS.iter().find(|(name, _)| name == control);
+
+        // Controls of the second player have no default host keys
+        if let (Some(key), Some((_, hosts))) = (key, hosts) {
+            for host in hosts.iter() {
+                bindings.push_str(&format!("{} {:X}\n", host, key as u8));
+            }
+        }
+    }
+
+    RomInfo {
+        hash: String::new(),
+        title: String::new(),
+        author: None,
+        platform: platform.map(|(_, platform)| *platform),
+        quirks,
+        tick_rate: rom
+            .get("tickrate")
+            .and_then(Value::as_f64)
+            .map(|rate| rate as u32),
+        colors,
+        bindings,
+    }
+}
+
+// Quirks of the database that have no equivalent keep the value of the platform
+fn apply_quirks(quirks: &mut Quirks, overrides: &Value) {
+    let quirk = |name| overrides.get(name).and_then(Value::as_bool);
+
+    if let Some(shift) = quirk("shift") {
+        quirks.shift = shift;
+    }
+
+    if let Some(jump) = quirk("jump") {
+        quirks.jump = jump;
+    }
+
+    if let Some(logic) = quirk("logic") {
+        quirks.vf_reset = logic;
+    }
+
+    if let Some(wrap) = quirk("wrap") {
+        quirks.clipping = !wrap;
+    }
+
+    if let Some(vblank) = quirk("vblank") {
+        quirks.display_wait = vblank;
+    }
+
+    // FX55/FX65 either increment I by X + 1, by X or leave it unchanged. The emulator has no way
+    // to increment I by X, so those ROMs leave it unchanged instead, which is lossy
+    match (quirk("memoryIncrementByX"), quirk("memoryLeaveIUnchanged")) {
+        (None, None) => {},
+        (by_x, unchanged) => {
+            quirks.memory_increment = !by_x.unwrap_or(false) && !unchanged.unwrap_or(false)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Database;
+    use crate::engine::quirks::Platform;
+
+    #[test]
+    fn embedded() {
+        let database = Database::new();
+        let info = database
+            .lookup("5C28A5F85289C9D859F95FD5EADBDCB1C30BB08B")
+            .unwrap();
+
+        assert_eq!(info.title, "Space Invaders");
+        assert_eq!(info.platform, Some(Platform::Chip48));
+        assert!(info.bindings.contains("GamepadA 5"));
+    }
+
+    #[test]
+    fn community_format() {
+        let mut database = Database::new();
+        let count = database
+            .merge(
+                r##"[{
+                    "title": "Test", "authors": ["A", "B"],
+                    "roms": {"00ff": {
+                        "platforms": ["megachip8", "superchip"],
+                        "tickrate": 100,
+                        "colors": {"pixels": ["#000000", "#ff8000"]},
+                        "keys": {"up": 2, "player2Up": 8, "a": 16},
+                        "quirkyPlatforms": {"superchip": {"wrap": true, "memoryIncrementByX": true}}
+                    }}
+                }]"##,
+            )
+            .unwrap();
+        let info = database.lookup("00FF").unwrap();
+
+        assert_eq!(count, 1);
+        assert_eq!(info.author.as_deref(), Some("A, B"));
+        assert_eq!(info.platform, Some(Platform::Schip));
+        assert_eq!(info.tick_rate, Some(100));
+        assert_eq!(info.colors, [0x000000, 0xFF8000]);
+        assert_eq!(info.bindings, "ArrowUp 2\nGamepadUp 2\n");
+
+        let quirks = info.quirks.unwrap();
+        assert!(!quirks.clipping);
+        assert!(!quirks.memory_increment);
+
+        // Only ROMs that were not known before are counted
+        assert_eq!(
+            database
+                .merge(r#"[{"title": "Other", "roms": {"00FF": {}, "0100": {}}}]"#)
+                .unwrap(),
+            1
+        );
+        assert_eq!(database.lookup("00ff").unwrap().title, "Other");
+    }
+}
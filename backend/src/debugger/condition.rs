@@ -0,0 +1,142 @@
+use crate::engine::Engine;
+
+use super::errors::DebuggerError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operand {
+    Register(u8),
+    Index,
+    Pc,
+    Sp,
+    DelayTimer,
+    SoundTimer,
+    Memory(u16),
+    Literal(u16),
+}
+
+impl Operand {
+    fn parse(token: &str) -> Option<Self> {
+        let token = token.trim();
+        let upper = token.to_ascii_uppercase();
+
+        match upper.as_str() {
+            "I" => return Some(Operand::Index),
+            "PC" => return Some(Operand::Pc),
+            "SP" => return Some(Operand::Sp),
+            "DT" => return Some(Operand::DelayTimer),
+            "ST" => return Some(Operand::SoundTimer),
+            _ => {},
+        }
+
+        if let Some(register) = upper.strip_prefix('V') {
+            return u8::from_str_radix(register, 16)
+                .ok()
+                .filter(|register| *register < 16)
+                .map(Operand::Register);
+        }
+
+        if let Some(address) = upper.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            return parse_number(address).map(Operand::Memory);
+        }
+
+        parse_number(&upper).map(Operand::Literal)
+    }
+
+    fn evaluate(&self, engine: &Engine) -> u16 {
+        match self {
+            Operand::Register(register) => engine.get_register(*register) as u16,
+            Operand::Index => engine.get_index(),
+            Operand::Pc => engine.get_pc(),
+            Operand::Sp => engine.get_sp() as u16,
+            Operand::DelayTimer => engine.get_delay_timer() as u16,
+            Operand::SoundTimer => engine.get_sound_timer() as u16,
+            Operand::Memory(address) => engine.peek_memory(*address) as u16,
+            Operand::Literal(value) => *value,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    const OPERATORS: [(&'static str, Comparison); 6] = [
+        ("==", Comparison::Equal),
+        ("!=", Comparison::NotEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ];
+
+    fn compare(&self, left: u16, right: u16) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Condition {
+    left: Operand,
+    comparison: Comparison,
+    right: Operand,
+}
+
+impl Condition {
+    pub fn parse(condition: &str) -> Result<Self, DebuggerError> {
+        let invalid = || DebuggerError::InvalidCondition {
+            condition: condition.to_string(),
+        };
+
+        let (operator, comparison) = Comparison::OPERATORS
+            .iter()
+            .find(|(operator, _)| condition.contains(operator))
+            .ok_or_else(invalid)?;
+
+        let (left, right) = condition.split_once(operator).ok_or_else(invalid)?;
+
+        Ok(Self {
+            left: Operand::parse(left).ok_or_else(invalid)?,
+            comparison: *comparison,
+            right: Operand::parse(right).ok_or_else(invalid)?,
+        })
+    }
+
+    pub fn evaluate(&self, engine: &Engine) -> bool {
+        self.comparison
+            .compare(self.left.evaluate(engine), self.right.evaluate(engine))
+    }
+}
+
+pub fn parse_number(token: &str) -> Option<u16> {
+    let token = token.trim();
+
+    if let Some(hex) = token
+        .strip_prefix("0x")
+        .or_else(|| token.strip_prefix("0X"))
+        .or_else(|| token.strip_prefix('#'))
+        .or_else(|| token.strip_prefix('$'))
+    {
+        u16::from_str_radix(hex, 16).ok()
+    } else if let Some(binary) = token
+        .strip_prefix("0b")
+        .or_else(|| token.strip_prefix("0B"))
+    {
+        u16::from_str_radix(binary, 2).ok()
+    } else {
+        token.parse().ok()
+    }
+}
@@ -0,0 +1,31 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum DebuggerError {
+    InvalidCondition { condition: String },
+    InvalidPattern { pattern: String },
+}
+
+impl DebuggerError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DebuggerError::InvalidCondition { .. } => "InvalidCondition",
+            DebuggerError::InvalidPattern { .. } => "InvalidPattern",
+        }
+    }
+}
+
+impl fmt::Display for DebuggerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DebuggerError::InvalidCondition { condition } => {
+                write!(f, "Invalid breakpoint condition: {:?}", condition)
+            },
+            DebuggerError::InvalidPattern { pattern } => {
+                write!(f, "Invalid opcode pattern: {:?}", pattern)
+            },
+        }
+    }
+}
+
+impl std::error::Error for DebuggerError {}
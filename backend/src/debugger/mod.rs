@@ -0,0 +1,328 @@
+use std::ops::Range;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::engine::Engine;
+
+use condition::Condition;
+use errors::DebuggerError;
+
+pub mod condition;
+pub mod errors;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl Access {
+    fn matches(&self, access: Access) -> bool {
+        *self == Access::ReadWrite || *self == access
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopKind {
+    Breakpoint,
+    Condition,
+    OpCode,
+    Watchpoint,
+    CycleLimit,
+    Halted,
+    VerticalBlank,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Debug)]
+pub struct StopReason {
+    pub kind: StopKind,
+    pub id: Option<u32>,
+    pub pc: u16,
+    pub opcode: Option<u16>,
+    pub address: Option<u16>,
+    pub cycles: u32,
+    pub symbol: Option<String>,
+}
+
+enum Breakpoint {
+    Address(u16),
+    Condition {
+        address: Option<u16>,
+        condition: Condition,
+    },
+    OpCode {
+        mask: u16,
+        value: u16,
+    },
+}
+
+struct Watchpoint {
+    range: Range<usize>,
+    access: Access,
+}
+
+pub struct WatchHit {
+    pub id: u32,
+    pub address: u16,
+}
+
+#[derive(Default)]
+pub struct Debugger {
+    breakpoints: Vec<(u32, Breakpoint)>,
+    watchpoints: Vec<(u32, Watchpoint)>,
+    next_id: u32,
+    hit: Option<WatchHit>,
+}
+
+impl Debugger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn next_id(&mut self) -> u32 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    pub fn add_breakpoint(&mut self, address: u16) -> u32 {
+        let id = self.next_id();
+        self.breakpoints.push((id, Breakpoint::Address(address)));
+
+        id
+    }
+
+    pub fn add_conditional_breakpoint(
+        &mut self,
+        condition: &str,
+        address: Option<u16>,
+    ) -> Result<u32, DebuggerError> {
+        let condition = Condition::parse(condition)?;
+
+        let id = self.next_id();
+        self.breakpoints
+            .push((id, Breakpoint::Condition { address, condition }));
+
+        Ok(id)
+    }
+
+    pub fn add_opcode_breakpoint(&mut self, pattern: &str) -> Result<u32, DebuggerError> {
+        let invalid = || DebuggerError::InvalidPattern {
+            pattern: pattern.to_string(),
+        };
+
+        let pattern = pattern.trim();
+
+        if pattern.len() != 4 {
+            Err(invalid())?;
+        }
+
+        let (mut mask, mut value) = (0u16, 0u16);
+
+        for digit in pattern.chars() {
+            mask <<= 4;
+            value <<= 4;
+
+            if let Some(nibble) = digit.to_digit(16) {
+                mask |= 0xF;
+                value |= nibble as u16;
+            } else if !matches!(digit.to_ascii_uppercase(), 'X' | 'Y' | 'N' | 'K' | '_') {
+                Err(invalid())?;
+            }
+        }
+
+        let id = self.next_id();
+        self.breakpoints
+            .push((id, Breakpoint::OpCode { mask, value }));
+
+        Ok(id)
+    }
+
+    pub fn add_watchpoint(&mut self, address: u16, length: u16, access: Access) -> u32 {
+        let start = address as usize;
+        let range = start..start + (length.max(1) as usize);
+
+        let id = self.next_id();
+        self.watchpoints.push((id, Watchpoint { range, access }));
+
+        id
+    }
+
+    pub fn remove(&mut self, id: u32) -> bool {
+        let count = self.breakpoints.len() + self.watchpoints.len();
+
+        self.breakpoints.retain(|(breakpoint, _)| *breakpoint != id);
+        self.watchpoints.retain(|(watchpoint, _)| *watchpoint != id);
+
+        count != self.breakpoints.len() + self.watchpoints.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.breakpoints.clear();
+        self.watchpoints.clear();
+        self.hit = None;
+    }
+
+    pub fn check(&self, engine: &Engine, opcode: u16) -> Option<(StopKind, u32)> {
+        let pc = engine.get_pc();
+
+        self.breakpoints
+            .iter()
+            .find_map(|(id, breakpoint)| match breakpoint {
+                Breakpoint::Address(address) if *address == pc => Some((StopKind::Breakpoint, *id)),
+                Breakpoint::Condition { address, condition }
+                    if address.is_none_or(|address| address == pc)
+                        && condition.evaluate(engine) =>
+                {
+                    Some((StopKind::Condition, *id))
+                },
+                Breakpoint::OpCode { mask, value } if opcode & mask == *value => {
+                    Some((StopKind::OpCode, *id))
+                },
+
+                _ => None,
+            })
+    }
+
+    pub fn on_access(&mut self, range: &Range<usize>, access: Access) {
+        if self.hit.is_some() {
+            return;
+        }
+
+        self.hit = self.watchpoints.iter().find_map(|(id, watchpoint)| {
+            let start = range.start.max(watchpoint.range.start);
+            let end = range.end.min(watchpoint.range.end);
+
+            (start < end && watchpoint.access.matches(access)).then_some(WatchHit {
+                id: *id,
+                address: start as u16,
+            })
+        });
+    }
+
+    pub fn take_hit(&mut self) -> Option<WatchHit> {
+        self.hit.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Access, StopKind};
+    use crate::engine::Engine;
+    use crate::engine::quirks::{Platform, Quirks};
+
+    // Counts in V0 and stores the count at 0x300 before reading it back
+    const ROM: [u8; 10] = [0x70, 0x01, 0xA3, 0x00, 0xF0, 0x55, 0xF0, 0x65, 0x12, 0x00];
+
+    fn engine() -> Engine {
+        let mut engine = Engine::new(Platform::Chip48, Quirks::new(Platform::Chip48));
+        engine.load_rom(&ROM).unwrap();
+        engine
+    }
+
+    #[test]
+    fn breakpoints() {
+        let mut engine = engine();
+        let id = engine.debugger().add_breakpoint(0x204);
+
+        let stop = engine.run_until_break(100).unwrap();
+        assert_eq!(
+            (stop.kind, stop.id, stop.pc),
+            (StopKind::Breakpoint, Some(id), 0x204)
+        );
+        assert_eq!((stop.opcode, stop.cycles), (Some(0xF055), 2));
+
+        // Running again does not stop on the same instruction before executing it
+        let stop = engine.run_until_break(100).unwrap();
+        assert_eq!((stop.pc, stop.cycles), (0x204, 5));
+
+        assert!(engine.debugger().remove(id));
+        assert!(!engine.debugger().remove(id));
+
+        let stop = engine.run_until_break(100).unwrap();
+        assert_eq!((stop.kind, stop.cycles), (StopKind::CycleLimit, 100));
+    }
+
+    #[test]
+    fn conditions() {
+        let mut engine = engine();
+        let id = engine
+            .debugger()
+            .add_conditional_breakpoint("V0 == 3", None)
+            .unwrap();
+
+        let stop = engine.run_until_break(100).unwrap();
+        assert_eq!(
+            (stop.kind, stop.id, stop.pc),
+            (StopKind::Condition, Some(id), 0x202)
+        );
+        assert_eq!(engine.get_register(0), 3);
+
+        // Conditions on the memory, only checked at an address
+        engine.debugger().clear();
+        engine
+            .debugger()
+            .add_conditional_breakpoint("[0x300] >= 5", Some(0x208))
+            .unwrap();
+
+        let stop = engine.run_until_break(100).unwrap();
+        assert_eq!(stop.pc, 0x208);
+        assert_eq!(engine.peek_memory(0x300), 5);
+
+        assert!(
+            engine
+                .debugger()
+                .add_conditional_breakpoint("V0 ~ 1", None)
+                .is_err()
+        );
+        assert!(
+            engine
+                .debugger()
+                .add_conditional_breakpoint("VG == 1", None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn opcodes() {
+        let mut engine = engine();
+        let id = engine.debugger().add_opcode_breakpoint("Fx65").unwrap();
+
+        let stop = engine.run_until_break(100).unwrap();
+        assert_eq!(
+            (stop.kind, stop.id, stop.pc),
+            (StopKind::OpCode, Some(id), 0x206)
+        );
+
+        assert!(engine.debugger().add_opcode_breakpoint("F65").is_err());
+        assert!(engine.debugger().add_opcode_breakpoint("FG65").is_err());
+    }
+
+    #[test]
+    fn watchpoints() {
+        let mut engine = engine();
+        let id = engine.debugger().add_watchpoint(0x2FF, 2, Access::Write);
+
+        // Stops after the instruction that accessed the memory, which is the one reported
+        let stop = engine.run_until_break(100).unwrap();
+        assert_eq!((stop.kind, stop.id), (StopKind::Watchpoint, Some(id)));
+        assert_eq!((stop.address, stop.pc), (Some(0x300), 0x204));
+        assert_eq!(engine.get_pc(), 0x206);
+
+        engine.debugger().clear();
+        engine.debugger().add_watchpoint(0x300, 1, Access::Read);
+        let stop = engine.run_until_break(100).unwrap();
+        assert_eq!((stop.address, stop.pc), (Some(0x300), 0x206));
+
+        // Memory next to the watched range is not reported
+        engine.debugger().clear();
+        engine
+            .debugger()
+            .add_watchpoint(0x301, 4, Access::ReadWrite);
+        let stop = engine.run_until_break(100).unwrap();
+        assert_eq!(stop.kind, StopKind::CycleLimit);
+    }
+}
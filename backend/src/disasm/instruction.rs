@@ -0,0 +1,345 @@
+use crate::engine::quirks::Platform;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    ScrollDown(u8),
+    ScrollUp(u8),
+    Clear,
+    Return,
+    ScrollRight,
+    ScrollLeft,
+    Exit,
+    LowResolution,
+    HighResolution,
+    Jump(u16),
+    Call(u16),
+    SkipEqualByte(u8, u8),
+    SkipNotEqualByte(u8, u8),
+    SkipEqual(u8, u8),
+    StoreRange(u8, u8),
+    LoadRange(u8, u8),
+    LoadByte(u8, u8),
+    AddByte(u8, u8),
+    Move(u8, u8),
+    Or(u8, u8),
+    And(u8, u8),
+    Xor(u8, u8),
+    Add(u8, u8),
+    Sub(u8, u8),
+    ShiftRight(u8, u8),
+    SubNegated(u8, u8),
+    ShiftLeft(u8, u8),
+    SkipNotEqual(u8, u8),
+    LoadIndex(u16),
+    JumpOffset(u16),
+    Random(u8, u8),
+    Draw(u8, u8, u8),
+    SkipKey(u8),
+    SkipNotKey(u8),
+    LoadLongIndex(u16),
+    Plane(u8),
+    Audio,
+    LoadDelay(u8),
+    WaitKey(u8),
+    SetDelay(u8),
+    SetSound(u8),
+    AddIndex(u8),
+    LoadFont(u8),
+    LoadLargeFont(u8),
+    Bcd(u8),
+    Pitch(u8),
+    StoreRegisters(u8),
+    LoadRegisters(u8),
+    StoreFlags(u8),
+    LoadFlags(u8),
+    Data(u16),
+}
+
+impl Instruction {
+    pub fn decode(opcode: u16, next: Option<u16>, platform: Platform) -> Self {
+        let prefix = ((opcode & 0xF000) >> 12) as u8;
+        let x = ((opcode & 0x0F00) >> 8) as u8;
+        let y = ((opcode & 0x00F0) >> 4) as u8;
+        let n = (opcode & 0x000F) as u8;
+        let nn = (opcode & 0x00FF) as u8;
+        let nnn = opcode & 0x0FFF;
+
+        let schip = platform.supports_schip();
+        let xo_chip = platform.supports_xo_chip();
+
+        match prefix {
+            0x0 => match nnn {
+                0x0C0..=0x0CF if schip => Instruction::ScrollDown(n),
+                0x0D0..=0x0DF if xo_chip => Instruction::ScrollUp(n),
+                0x0E0 => Instruction::Clear,
+                0x0EE => Instruction::Return,
+                0x0FB if schip => Instruction::ScrollRight,
+                0x0FC if schip => Instruction::ScrollLeft,
+                0x0FD if schip => Instruction::Exit,
+                0x0FE if schip => Instruction::LowResolution,
+                0x0FF if schip => Instruction::HighResolution,
+
+                _ => Instruction::Data(opcode),
+            },
+            0x1 => Instruction::Jump(nnn),
+            0x2 => Instruction::Call(nnn),
+            0x3 => Instruction::SkipEqualByte(x, nn),
+            0x4 => Instruction::SkipNotEqualByte(x, nn),
+            0x5 => match n {
+                0x0 => Instruction::SkipEqual(x, y),
+                0x2 if xo_chip => Instruction::StoreRange(x, y),
+                0x3 if xo_chip => Instruction::LoadRange(x, y),
+
+                _ => Instruction::Data(opcode),
+            },
+            0x6 => Instruction::LoadByte(x, nn),
+            0x7 => Instruction::AddByte(x, nn),
+            0x8 => match n {
+                0x0 => Instruction::Move(x, y),
+                0x1 => Instruction::Or(x, y),
+                0x2 => Instruction::And(x, y),
+                0x3 => Instruction::Xor(x, y),
+                0x4 => Instruction::Add(x, y),
+                0x5 => Instruction::Sub(x, y),
+                0x6 => Instruction::ShiftRight(x, y),
+                0x7 => Instruction::SubNegated(x, y),
+                0xE => Instruction::ShiftLeft(x, y),
+
+                _ => Instruction::Data(opcode),
+            },
+            0x9 if n == 0 => Instruction::SkipNotEqual(x, y),
+            0xA => Instruction::LoadIndex(nnn),
+            0xB => Instruction::JumpOffset(nnn),
+            0xC => Instruction::Random(x, nn),
+            0xD => Instruction::Draw(x, y, n),
+            0xE => match nn {
+                0x9E => Instruction::SkipKey(x),
+                0xA1 => Instruction::SkipNotKey(x),
+
+                _ => Instruction::Data(opcode),
+            },
+            0xF => match nn {
+                0x00 if x == 0 && xo_chip => match next {
+                    Some(address) => Instruction::LoadLongIndex(address),
+                    None => Instruction::Data(opcode),
+                },
+                0x01 if xo_chip => Instruction::Plane(x),
+                0x02 if x == 0 && xo_chip => Instruction::Audio,
+                0x07 => Instruction::LoadDelay(x),
+                0x0A => Instruction::WaitKey(x),
+                0x15 => Instruction::SetDelay(x),
+                0x18 => Instruction::SetSound(x),
+                0x1E => Instruction::AddIndex(x),
+                0x29 => Instruction::LoadFont(x),
+                0x30 if schip => Instruction::LoadLargeFont(x),
+                0x33 => Instruction::Bcd(x),
+                0x3A if xo_chip => Instruction::Pitch(x),
+                0x55 => Instruction::StoreRegisters(x),
+                0x65 => Instruction::LoadRegisters(x),
+                0x75 if schip => Instruction::StoreFlags(x),
+                0x85 if schip => Instruction::LoadFlags(x),
+
+                _ => Instruction::Data(opcode),
+            },
+
+            _ => Instruction::Data(opcode),
+        }
+    }
+
+    pub fn size(&self) -> u16 {
+        match self {
+            Instruction::LoadLongIndex(_) => 4,
+
+            _ => 2,
+        }
+    }
+
+    pub fn target(&self) -> Option<u16> {
+        match *self {
+            Instruction::Jump(nnn)
+            | Instruction::Call(nnn)
+            | Instruction::LoadIndex(nnn)
+            | Instruction::JumpOffset(nnn)
+            | Instruction::LoadLongIndex(nnn) => Some(nnn),
+
+            _ => None,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let xy = |prefix: u16, x: u8, y: u8, n: u16| {
+            prefix << 12 | (x as u16 & 0xF) << 8 | (y as u16 & 0xF) << 4 | n
+        };
+        let xnn = |prefix: u16, x: u8, nn: u8| prefix << 12 | (x as u16 & 0xF) << 8 | nn as u16;
+
+        let opcode = match *self {
+            Instruction::ScrollDown(n) => 0x00C0 | (n as u16 & 0xF),
+            Instruction::ScrollUp(n) => 0x00D0 | (n as u16 & 0xF),
+            Instruction::Clear => 0x00E0,
+            Instruction::Return => 0x00EE,
+            Instruction::ScrollRight => 0x00FB,
+            Instruction::ScrollLeft => 0x00FC,
+            Instruction::Exit => 0x00FD,
+            Instruction::LowResolution => 0x00FE,
+            Instruction::HighResolution => 0x00FF,
+            Instruction::Jump(nnn) => 0x1000 | (nnn & 0x0FFF),
+            Instruction::Call(nnn) => 0x2000 | (nnn & 0x0FFF),
+            Instruction::SkipEqualByte(x, nn) => xnn(0x3, x, nn),
+            Instruction::SkipNotEqualByte(x, nn) => xnn(0x4, x, nn),
+            Instruction::SkipEqual(x, y) => xy(0x5, x, y, 0x0),
+            Instruction::StoreRange(x, y) => xy(0x5, x, y, 0x2),
+            Instruction::LoadRange(x, y) => xy(0x5, x, y, 0x3),
+            Instruction::LoadByte(x, nn) => xnn(0x6, x, nn),
+            Instruction::AddByte(x, nn) => xnn(0x7, x, nn),
+            Instruction::Move(x, y) => xy(0x8, x, y, 0x0),
+            Instruction::Or(x, y) => xy(0x8, x, y, 0x1),
+            Instruction::And(x, y) => xy(0x8, x, y, 0x2),
+            Instruction::Xor(x, y) => xy(0x8, x, y, 0x3),
+            Instruction::Add(x, y) => xy(0x8, x, y, 0x4),
+            Instruction::Sub(x, y) => xy(0x8, x, y, 0x5),
+            Instruction::ShiftRight(x, y) => xy(0x8, x, y, 0x6),
+            Instruction::SubNegated(x, y) => xy(0x8, x, y, 0x7),
+            Instruction::ShiftLeft(x, y) => xy(0x8, x, y, 0xE),
+            Instruction::SkipNotEqual(x, y) => xy(0x9, x, y, 0x0),
+            Instruction::LoadIndex(nnn) => 0xA000 | (nnn & 0x0FFF),
+            Instruction::JumpOffset(nnn) => 0xB000 | (nnn & 0x0FFF),
+            Instruction::Random(x, nn) => xnn(0xC, x, nn),
+            Instruction::Draw(x, y, n) => xy(0xD, x, y, n as u16 & 0xF),
+            Instruction::SkipKey(x) => xnn(0xE, x, 0x9E),
+            Instruction::SkipNotKey(x) => xnn(0xE, x, 0xA1),
+            Instruction::LoadLongIndex(nnnn) => {
+                return vec![0xF0, 0x00, (nnnn >> 8) as u8, nnnn as u8];
+            },
+            Instruction::Plane(n) => xnn(0xF, n, 0x01),
+            Instruction::Audio => 0xF002,
+            Instruction::LoadDelay(x) => xnn(0xF, x, 0x07),
+            Instruction::WaitKey(x) => xnn(0xF, x, 0x0A),
+            Instruction::SetDelay(x) => xnn(0xF, x, 0x15),
+            Instruction::SetSound(x) => xnn(0xF, x, 0x18),
+            Instruction::AddIndex(x) => xnn(0xF, x, 0x1E),
+            Instruction::LoadFont(x) => xnn(0xF, x, 0x29),
+            Instruction::LoadLargeFont(x) => xnn(0xF, x, 0x30),
+            Instruction::Bcd(x) => xnn(0xF, x, 0x33),
+            Instruction::Pitch(x) => xnn(0xF, x, 0x3A),
+            Instruction::StoreRegisters(x) => xnn(0xF, x, 0x55),
+            Instruction::LoadRegisters(x) => xnn(0xF, x, 0x65),
+            Instruction::StoreFlags(x) => xnn(0xF, x, 0x75),
+            Instruction::LoadFlags(x) => xnn(0xF, x, 0x85),
+            Instruction::Data(opcode) => opcode,
+        };
+
+        opcode.to_be_bytes().to_vec()
+    }
+
+    pub fn classic(&self) -> String {
+        match *self {
+            Instruction::ScrollDown(n) => format!("SCD {}", n),
+            Instruction::ScrollUp(n) => format!("SCU {}", n),
+            Instruction::Clear => "CLS".to_string(),
+            Instruction::Return => "RET".to_string(),
+            Instruction::ScrollRight => "SCR".to_string(),
+            Instruction::ScrollLeft => "SCL".to_string(),
+            Instruction::Exit => "EXIT".to_string(),
+            Instruction::LowResolution => "LOW".to_string(),
+            Instruction::HighResolution => "HIGH".to_string(),
+            Instruction::Jump(nnn) => format!("JP {:#05X}", nnn),
+            Instruction::Call(nnn) => format!("CALL {:#05X}", nnn),
+            Instruction::SkipEqualByte(x, nn) => format!("SE V{:X}, {:#04X}", x, nn),
+            Instruction::SkipNotEqualByte(x, nn) => format!("SNE V{:X}, {:#04X}", x, nn),
+            Instruction::SkipEqual(x, y) => format!("SE V{:X}, V{:X}", x, y),
+            Instruction::StoreRange(x, y) => format!("LD [I], V{:X}-V{:X}", x, y),
+            Instruction::LoadRange(x, y) => format!("LD V{:X}-V{:X}, [I]", x, y),
+            Instruction::LoadByte(x, nn) => format!("LD V{:X}, {:#04X}", x, nn),
+            Instruction::AddByte(x, nn) => format!("ADD V{:X}, {:#04X}", x, nn),
+            Instruction::Move(x, y) => format!("LD V{:X}, V{:X}", x, y),
+            Instruction::Or(x, y) => format!("OR V{:X}, V{:X}", x, y),
+            Instruction::And(x, y) => format!("AND V{:X}, V{:X}", x, y),
+            Instruction::Xor(x, y) => format!("XOR V{:X}, V{:X}", x, y),
+            Instruction::Add(x, y) => format!("ADD V{:X}, V{:X}", x, y),
+            Instruction::Sub(x, y) => format!("SUB V{:X}, V{:X}", x, y),
+            Instruction::ShiftRight(x, y) => format!("SHR V{:X}, V{:X}", x, y),
+            Instruction::SubNegated(x, y) => format!("SUBN V{:X}, V{:X}", x, y),
+            Instruction::ShiftLeft(x, y) => format!("SHL V{:X}, V{:X}", x, y),
+            Instruction::SkipNotEqual(x, y) => format!("SNE V{:X}, V{:X}", x, y),
+            Instruction::LoadIndex(nnn) => format!("LD I, {:#05X}", nnn),
+            Instruction::JumpOffset(nnn) => format!("JP V0, {:#05X}", nnn),
+            Instruction::Random(x, nn) => format!("RND V{:X}, {:#04X}", x, nn),
+            Instruction::Draw(x, y, n) => format!("DRW V{:X}, V{:X}, {}", x, y, n),
+            Instruction::SkipKey(x) => format!("SKP V{:X}", x),
+            Instruction::SkipNotKey(x) => format!("SKNP V{:X}", x),
+            Instruction::LoadLongIndex(nnnn) => format!("LD I, {:#06X}", nnnn),
+            Instruction::Plane(n) => format!("PLANE {}", n),
+            Instruction::Audio => "AUDIO".to_string(),
+            Instruction::LoadDelay(x) => format!("LD V{:X}, DT", x),
+            Instruction::WaitKey(x) => format!("LD V{:X}, K", x),
+            Instruction::SetDelay(x) => format!("LD DT, V{:X}", x),
+            Instruction::SetSound(x) => format!("LD ST, V{:X}", x),
+            Instruction::AddIndex(x) => format!("ADD I, V{:X}", x),
+            Instruction::LoadFont(x) => format!("LD F, V{:X}", x),
+            Instruction::LoadLargeFont(x) => format!("LD HF, V{:X}", x),
+            Instruction::Bcd(x) => format!("LD B, V{:X}", x),
+            Instruction::Pitch(x) => format!("PITCH V{:X}", x),
+            Instruction::StoreRegisters(x) => format!("LD [I], V{:X}", x),
+            Instruction::LoadRegisters(x) => format!("LD V{:X}, [I]", x),
+            Instruction::StoreFlags(x) => format!("LD R, V{:X}", x),
+            Instruction::LoadFlags(x) => format!("LD V{:X}, R", x),
+            Instruction::Data(opcode) => format!("DW {:#06X}", opcode),
+        }
+    }
+
+    pub fn octo(&self) -> String {
+        match *self {
+            Instruction::ScrollDown(n) => format!("scroll-down {}", n),
+            Instruction::ScrollUp(n) => format!("scroll-up {}", n),
+            Instruction::Clear => "clear".to_string(),
+            Instruction::Return => "return".to_string(),
+            Instruction::ScrollRight => "scroll-right".to_string(),
+            Instruction::ScrollLeft => "scroll-left".to_string(),
+            Instruction::Exit => "exit".to_string(),
+            Instruction::LowResolution => "lores".to_string(),
+            Instruction::HighResolution => "hires".to_string(),
+            Instruction::Jump(nnn) => format!("jump {:#05X}", nnn),
+            Instruction::Call(nnn) => format!(":call {:#05X}", nnn),
+            Instruction::SkipEqualByte(x, nn) => format!("if v{:x} != {:#04X} then", x, nn),
+            Instruction::SkipNotEqualByte(x, nn) => format!("if v{:x} == {:#04X} then", x, nn),
+            Instruction::SkipEqual(x, y) => format!("if v{:x} != v{:x} then", x, y),
+            Instruction::StoreRange(x, y) => format!("save v{:x} - v{:x}", x, y),
+            Instruction::LoadRange(x, y) => format!("load v{:x} - v{:x}", x, y),
+            Instruction::LoadByte(x, nn) => format!("v{:x} := {:#04X}", x, nn),
+            Instruction::AddByte(x, nn) => format!("v{:x} += {:#04X}", x, nn),
+            Instruction::Move(x, y) => format!("v{:x} := v{:x}", x, y),
+            Instruction::Or(x, y) => format!("v{:x} |= v{:x}", x, y),
+            Instruction::And(x, y) => format!("v{:x} &= v{:x}", x, y),
+            Instruction::Xor(x, y) => format!("v{:x} ^= v{:x}", x, y),
+            Instruction::Add(x, y) => format!("v{:x} += v{:x}", x, y),
+            Instruction::Sub(x, y) => format!("v{:x} -= v{:x}", x, y),
+            Instruction::ShiftRight(x, y) => format!("v{:x} >>= v{:x}", x, y),
+            Instruction::SubNegated(x, y) => format!("v{:x} =- v{:x}", x, y),
+            Instruction::ShiftLeft(x, y) => format!("v{:x} <<= v{:x}", x, y),
+            Instruction::SkipNotEqual(x, y) => format!("if v{:x} == v{:x} then", x, y),
+            Instruction::LoadIndex(nnn) => format!("i := {:#05X}", nnn),
+            Instruction::JumpOffset(nnn) => format!("jump0 {:#05X}", nnn),
+            Instruction::Random(x, nn) => format!("v{:x} := random {:#04X}", x, nn),
+            Instruction::Draw(x, y, n) => format!("sprite v{:x} v{:x} {}", x, y, n),
+            Instruction::SkipKey(x) => format!("if v{:x} -key then", x),
+            Instruction::SkipNotKey(x) => format!("if v{:x} key then", x),
+            Instruction::LoadLongIndex(nnnn) => format!("i := long {:#06X}", nnnn),
+            Instruction::Plane(n) => format!("plane {}", n),
+            Instruction::Audio => "audio".to_string(),
+            Instruction::LoadDelay(x) => format!("v{:x} := delay", x),
+            Instruction::WaitKey(x) => format!("v{:x} := key", x),
+            Instruction::SetDelay(x) => format!("delay := v{:x}", x),
+            Instruction::SetSound(x) => format!("buzzer := v{:x}", x),
+            Instruction::AddIndex(x) => format!("i += v{:x}", x),
+            Instruction::LoadFont(x) => format!("i := hex v{:x}", x),
+            Instruction::LoadLargeFont(x) => format!("i := bighex v{:x}", x),
+            Instruction::Bcd(x) => format!("bcd v{:x}", x),
+            Instruction::Pitch(x) => format!("pitch := v{:x}", x),
+            Instruction::StoreRegisters(x) => format!("save v{:x}", x),
+            Instruction::LoadRegisters(x) => format!("load v{:x}", x),
+            Instruction::StoreFlags(x) => format!("saveflags v{:x}", x),
+            Instruction::LoadFlags(x) => format!("loadflags v{:x}", x),
+            Instruction::Data(opcode) => format!("{:#04X} {:#04X}", opcode >> 8, opcode & 0xFF),
+        }
+    }
+}
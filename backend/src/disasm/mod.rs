@@ -0,0 +1,151 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::engine::quirks::Platform;
+use crate::symbols::SymbolMap;
+
+use instruction::Instruction;
+
+pub mod instruction;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Syntax {
+    #[default]
+    Classic,
+    Octo,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Debug)]
+pub struct DisassembledLine {
+    pub address: u16,
+    pub bytes: Vec<u8>,
+    pub text: String,
+    pub symbol: Option<String>,
+}
+
+pub fn format(instruction: &Instruction, syntax: Syntax) -> String {
+    match syntax {
+        Syntax::Classic => instruction.classic(),
+        Syntax::Octo => instruction.octo(),
+    }
+}
+
+fn read_word(memory: &[u8], address: usize) -> Option<u16> {
+    Some(((*memory.get(address)? as u16) << 8) | *memory.get(address + 1)? as u16)
+}
+
+pub fn disassemble(
+    memory: &[u8],
+    address: u16,
+    count: usize,
+    platform: Platform,
+    syntax: Syntax,
+    symbols: &SymbolMap,
+) -> Vec<DisassembledLine> {
+    let mut lines = Vec::with_capacity(count);
+    let mut address = address as usize;
+
+    while lines.len() < count {
+        let Some(opcode) = read_word(memory, address) else {
+            break;
+        };
+
+        let instruction = Instruction::decode(opcode, read_word(memory, address + 2), platform);
+        let size = instruction.size() as usize;
+        let mut text = format(&instruction, syntax);
+
+        if let Some(target) = instruction.target()
+            && let Some(symbol) = symbols.lookup(target)
+        {
+            let literal = match instruction {
+                Instruction::LoadLongIndex(_) => format!("{:#06X}", target),
+
+                _ => format!("{:#05X}", target),
+            };
+
+            text = text.replace(&literal, &symbol);
+        }
+
+        lines.push(DisassembledLine {
+            address: address as u16,
+            bytes: memory[address..address + size].to_vec(),
+            text,
+            symbol: symbols.lookup(address as u16),
+        });
+
+        address += size;
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Syntax, disassemble};
+    use crate::engine::quirks::Platform;
+    use crate::symbols::SymbolMap;
+
+    const PROGRAM: [u8; 14] = [
+        0x6A, 0x0F, 0x22, 0x08, 0xF0, 0x00, 0x12, 0x34, 0x91, 0x21, 0x00, 0xFF, 0x00, 0xEE,
+    ];
+
+    fn text(platform: Platform, syntax: Syntax, symbols: &SymbolMap) -> Vec<String> {
+        disassemble(&PROGRAM, 0, 16, platform, syntax, symbols)
+            .into_iter()
+            .map(|line| line.text)
+            .collect()
+    }
+
+    #[test]
+    fn classic() {
+        assert_eq!(
+            text(Platform::XoChip, Syntax::Classic, &SymbolMap::new()),
+            [
+                "LD VA, 0x0F",
+                "CALL 0x208",
+                "LD I, 0x1234",
+                "DW 0x9121",
+                "HIGH",
+                "RET"
+            ]
+        );
+
+        // Instructions of later platforms are data on earlier ones
+        assert_eq!(
+            text(Platform::Chip48, Syntax::Classic, &SymbolMap::new())[2..],
+            ["DW 0xF000", "JP 0x234", "DW 0x9121", "DW 0x00FF", "RET"]
+        );
+    }
+
+    #[test]
+    fn octo() {
+        assert_eq!(
+            text(Platform::XoChip, Syntax::Octo, &SymbolMap::new()),
+            [
+                "va := 0x0F",
+                ":call 0x208",
+                "i := long 0x1234",
+                "0x91 0x21",
+                "hires",
+                "return"
+            ]
+        );
+    }
+
+    #[test]
+    fn symbols() {
+        let mut symbols = SymbolMap::new();
+        symbols.insert(0x0002, "start");
+        symbols.insert(0x0208, "draw");
+        symbols.insert(0x1234, "data");
+
+        let lines = disassemble(&PROGRAM, 0, 3, Platform::XoChip, Syntax::Classic, &symbols);
+
+        assert_eq!(lines[1].symbol.as_deref(), Some("start"));
+        assert_eq!(lines[1].text, "CALL draw");
+        assert_eq!(lines[2].text, "LD I, data");
+        assert_eq!(lines[2].bytes, [0xF0, 0x00, 0x12, 0x34]);
+        assert_eq!(lines[2].address, 4);
+    }
+}
@@ -1,6 +1,13 @@
 pub const WIDTH: usize = 64;
 pub const HEIGHT: usize = 32;
 
+pub const HIGH_RES_WIDTH: usize = 128;
+pub const HIGH_RES_HEIGHT: usize = 64;
+
+pub const PLANE_COUNT: usize = 2;
+
+pub const LARGE_FONT_ADDRESS: usize = 0x50;
+
 pub const FONT_SET: [u8; 80] = [
     0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
     0x20, 0x60, 0x20, 0x20, 0x70, // 1
@@ -19,3 +26,45 @@ pub const FONT_SET: [u8; 80] = [
     0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
     0xF0, 0x80, 0xF0, 0x80, 0x80, // F
 ];
+
+pub const LARGE_FONT_SET: [u8; 100] = [
+    0x3C, 0x7E, 0xE7, 0xC3, 0xC3, 0xC3, 0xC3, 0xE7, 0x7E, 0x3C, // 0
+    0x18, 0x38, 0x58, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x3C, // 1
+    0x3E, 0x7F, 0xC3, 0x06, 0x0C, 0x18, 0x30, 0x60, 0xFF, 0xFF, // 2
+    0x3C, 0x7E, 0xC3, 0x03, 0x0E, 0x0E, 0x03, 0xC3, 0x7E, 0x3C, // 3
+    0x06, 0x0E, 0x1E, 0x36, 0x66, 0xC6, 0xFF, 0xFF, 0x06, 0x06, // 4
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFC, 0xFE, 0x03, 0xC3, 0x7E, 0x3C, // 5
+    0x3E, 0x7C, 0xE0, 0xC0, 0xFC, 0xFE, 0xC3, 0xC3, 0x7E, 0x3C, // 6
+    0xFF, 0xFF, 0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x60, 0x60, // 7
+    0x3C, 0x7E, 0xC3, 0xC3, 0x7E, 0x7E, 0xC3, 0xC3, 0x7E, 0x3C, // 8
+    0x3C, 0x7E, 0xC3, 0xC3, 0x7F, 0x3F, 0x03, 0x03, 0x3E, 0x7C, // 9
+];
+
+pub const MAX_SCALE: usize = 8;
+pub const SCANLINE_INTENSITY: f32 = 0.6;
+
+// Colors for the background, the first plane, the second plane and both planes
+pub const GREEN_PALETTE: [[u8; 3]; 4] = [
+    [0x00, 0x00, 0x00],
+    [0x33, 0xFF, 0x66],
+    [0xFF, 0xAA, 0x00],
+    [0xFF, 0xFF, 0xFF],
+];
+pub const AMBER_PALETTE: [[u8; 3]; 4] = [
+    [0x00, 0x00, 0x00],
+    [0xFF, 0xB0, 0x00],
+    [0x99, 0x55, 0x00],
+    [0xFF, 0xE0, 0xA0],
+];
+pub const WHITE_PALETTE: [[u8; 3]; 4] = [
+    [0x00, 0x00, 0x00],
+    [0xFF, 0xFF, 0xFF],
+    [0xAA, 0xAA, 0xAA],
+    [0x55, 0x55, 0x55],
+];
+pub const OCTO_PALETTE: [[u8; 3]; 4] = [
+    [0x99, 0x66, 0x00],
+    [0xFF, 0xCC, 0x00],
+    [0xFF, 0x66, 0x00],
+    [0x66, 0x22, 0x00],
+];
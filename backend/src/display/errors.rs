@@ -1,23 +1,33 @@
-use crate::error::ErrorTrait;
+use std::fmt;
 
+#[derive(Debug)]
 pub enum DisplayError {
     OutOfBounds { x: u8, y: u8, width: u8, height: u8 },
 }
 
-impl ErrorTrait for DisplayError {
-    fn to_string(&self) -> String {
+impl DisplayError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DisplayError::OutOfBounds { .. } => "DisplayOutOfBounds",
+        }
+    }
+}
+
+impl fmt::Display for DisplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DisplayError::OutOfBounds {
                 x,
                 y,
                 width,
                 height,
-            } => {
-                format!(
-                    "Coordinates out of bounds: ({}, {}) for display size ({}, {})",
-                    x, y, width, height
-                )
-            },
+            } => write!(
+                f,
+                "Coordinates out of bounds: ({}, {}) for display size ({}, {})",
+                x, y, width, height
+            ),
         }
     }
 }
+
+impl std::error::Error for DisplayError {}
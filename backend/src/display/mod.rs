@@ -1,78 +1,264 @@
-use constants::{HEIGHT, WIDTH};
+use crate::state::errors::StateError;
+use crate::state::{Decoder, Encoder, Snapshot};
+
+use constants::{HEIGHT, HIGH_RES_HEIGHT, HIGH_RES_WIDTH, PLANE_COUNT, WIDTH};
 use errors::DisplayError;
+use region::DirtyRegion;
 
 pub mod constants;
 pub mod errors;
+pub mod region;
+pub mod render;
 
 pub struct Display {
-    memory: [u8; WIDTH * HEIGHT],
+    memory: [u8; HIGH_RES_WIDTH * HIGH_RES_HEIGHT],
+    high_resolution: bool,
+    planes: u8,
+    revision: u32,
+    // Rows and bounding box (left, top, right, bottom) of the pixels changed since the last read
+    dirty_rows: u64,
+    dirty_rect: Option<(usize, usize, usize, usize)>,
 }
 
 impl Display {
     pub fn new() -> Self {
-        Self {
-            memory: [0; WIDTH * HEIGHT],
+        let mut display = Self {
+            memory: [0; HIGH_RES_WIDTH * HIGH_RES_HEIGHT],
+            high_resolution: false,
+            planes: 0x1,
+            revision: 0,
+            dirty_rows: 0,
+            dirty_rect: None,
+        };
+
+        display.mark_all();
+        display
+    }
+
+    pub fn get_memory(&self) -> &[u8] {
+        &self.memory[..self.get_width() * self.get_height()]
+    }
+
+    pub fn get_width(&self) -> usize {
+        if self.high_resolution {
+            HIGH_RES_WIDTH
+        } else {
+            WIDTH
         }
     }
 
-    pub fn get_memory(&self) -> &[u8; WIDTH * HEIGHT] {
-        &self.memory
+    pub fn get_height(&self) -> usize {
+        if self.high_resolution {
+            HIGH_RES_HEIGHT
+        } else {
+            HEIGHT
+        }
+    }
+
+    // Increases every time the contents or the size of the display change
+    pub fn get_revision(&self) -> u32 {
+        self.revision
+    }
+
+    fn mark(&mut self, x: usize, y: usize) {
+        self.revision = self.revision.wrapping_add(1);
+        self.dirty_rows |= 1 << y;
+
+        self.dirty_rect = Some(match self.dirty_rect {
+            Some((left, top, right, bottom)) => {
+                (left.min(x), top.min(y), right.max(x), bottom.max(y))
+            },
+            None => (x, y, x, y),
+        });
+    }
+
+    fn mark_all(&mut self) {
+        let (width, height) = (self.get_width(), self.get_height());
+
+        self.revision = self.revision.wrapping_add(1);
+        self.dirty_rows = u64::MAX >> (u64::BITS as usize - height);
+        self.dirty_rect = Some((0, 0, width - 1, height - 1));
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty_rect.is_some()
+    }
+
+    // Returns the area changed since the previous call, if any
+    pub fn take_dirty_region(&mut self) -> Option<DirtyRegion> {
+        let (left, top, right, bottom) = self.dirty_rect.take()?;
+        let rows = std::mem::take(&mut self.dirty_rows);
+
+        Some(DirtyRegion::new(
+            left,
+            top,
+            right - left + 1,
+            bottom - top + 1,
+            rows,
+        ))
     }
 
-    fn set_pixel(&mut self, x: usize, y: usize, value: bool) -> Result<(), DisplayError> {
-        if x >= WIDTH || y >= HEIGHT {
+    pub fn set_high_resolution(&mut self, high_resolution: bool) -> Result<(), DisplayError> {
+        self.high_resolution = high_resolution;
+        self.memory.fill(0);
+        self.mark_all();
+
+        Ok(())
+    }
+
+    pub fn get_planes(&self) -> u8 {
+        self.planes
+    }
+
+    pub fn set_planes(&mut self, planes: u8) {
+        self.planes = planes & ((1 << PLANE_COUNT) - 1);
+    }
+
+    pub fn get_plane_count(&self) -> usize {
+        self.planes.count_ones() as usize
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize, value: u8) -> Result<(), DisplayError> {
+        let (width, height) = (self.get_width(), self.get_height());
+
+        if x >= width || y >= height {
             Err(DisplayError::OutOfBounds {
                 x: x as u8,
                 y: y as u8,
-                width: WIDTH as u8,
-                height: HEIGHT as u8,
+                width: width as u8,
+                height: height as u8,
             })?;
         }
 
-        self.memory[x + y * WIDTH] = value as u8;
+        if self.memory[x + y * width] != value {
+            self.memory[x + y * width] = value;
+            self.mark(x, y);
+        }
 
         Ok(())
     }
 
     fn get_pixel(&self, x: usize, y: usize) -> Result<u8, DisplayError> {
-        if x >= WIDTH || y >= HEIGHT {
+        let (width, height) = (self.get_width(), self.get_height());
+
+        if x >= width || y >= height {
             Err(DisplayError::OutOfBounds {
                 x: x as u8,
                 y: y as u8,
-                width: WIDTH as u8,
-                height: HEIGHT as u8,
+                width: width as u8,
+                height: height as u8,
             })?;
         }
 
-        Ok(self.memory[x + y * WIDTH])
+        Ok(self.memory[x + y * width])
     }
 
     pub fn clear(&mut self) -> Result<(), DisplayError> {
-        for x in 0..WIDTH {
-            for y in 0..HEIGHT {
-                self.set_pixel(x, y, false)?;
+        if self
+            .get_memory()
+            .iter()
+            .any(|pixel| pixel & self.planes != 0)
+        {
+            self.mark_all();
+        }
+
+        for pixel in self.memory.iter_mut() {
+            *pixel &= !self.planes;
+        }
+
+        Ok(())
+    }
+
+    fn scroll(&mut self, dx: isize, dy: isize) -> Result<(), DisplayError> {
+        let (width, height) = (self.get_width(), self.get_height());
+        let previous = self.memory;
+
+        for y in 0..height {
+            for x in 0..width {
+                let (sx, sy) = (x as isize - dx, y as isize - dy);
+
+                let shifted = if sx >= 0 && sy >= 0 && sx < width as isize && sy < height as isize {
+                    previous[sx as usize + sy as usize * width]
+                } else {
+                    0
+                };
+
+                let old_value = self.get_pixel(x, y)?;
+                self.set_pixel(x, y, (old_value & !self.planes) | (shifted & self.planes))?;
             }
         }
 
         Ok(())
     }
 
-    pub fn draw(&mut self, x: usize, y: usize, sprite: &[u8]) -> Result<bool, DisplayError> {
+    pub fn scroll_down(&mut self, rows: usize) -> Result<(), DisplayError> {
+        self.scroll(0, rows as isize)
+    }
+
+    pub fn scroll_up(&mut self, rows: usize) -> Result<(), DisplayError> {
+        self.scroll(0, -(rows as isize))
+    }
+
+    pub fn scroll_right(&mut self, columns: usize) -> Result<(), DisplayError> {
+        self.scroll(columns as isize, 0)
+    }
+
+    pub fn scroll_left(&mut self, columns: usize) -> Result<(), DisplayError> {
+        self.scroll(-(columns as isize), 0)
+    }
+
+    pub fn draw(
+        &mut self,
+        x: usize,
+        y: usize,
+        sprite: &[u8],
+        wide: bool,
+        clipping: bool,
+    ) -> Result<bool, DisplayError> {
+        let (width, height) = (self.get_width(), self.get_height());
+        let sprite_width = if wide { 16 } else { 8 };
+        let planes = self.planes;
         let mut collision = false;
 
-        for (j, row) in sprite.iter().enumerate() {
-            for i in 0..8 {
-                let new_value = (row >> (7 - i)) & 0x01;
+        if sprite.is_empty() || planes == 0 {
+            return Ok(collision);
+        }
 
-                if new_value == 1 {
-                    let xi = (x + i) % WIDTH;
-                    let yj = (y + j) % HEIGHT;
+        let x = x % width;
+        let y = y % height;
 
-                    let old_value = self.get_pixel(xi, yj)?;
+        let selected = (0..PLANE_COUNT).filter(|plane| planes & (1 << plane) != 0);
+        let plane_size = (sprite.len() / self.get_plane_count()).max(1);
 
-                    collision = collision || (old_value == 1);
+        for (plane, data) in selected.zip(sprite.chunks(plane_size)) {
+            let bit = 1 << plane;
 
-                    self.set_pixel(xi, yj, (new_value == 1) ^ (old_value == 1))?;
+            for (j, bytes) in data.chunks(sprite_width / 8).enumerate() {
+                if clipping && y + j >= height {
+                    break;
+                }
+
+                let row = bytes
+                    .iter()
+                    .fold(0u16, |row, byte| (row << 8) | *byte as u16);
+
+                for i in 0..sprite_width {
+                    if clipping && x + i >= width {
+                        break;
+                    }
+
+                    let new_value = (row >> (sprite_width - 1 - i)) & 0x01;
+
+                    if new_value == 1 {
+                        let xi = (x + i) % width;
+                        let yj = (y + j) % height;
+
+                        let old_value = self.get_pixel(xi, yj)?;
+
+                        collision = collision || (old_value & bit != 0);
+
+                        self.set_pixel(xi, yj, old_value ^ bit)?;
+                    }
                 }
             }
         }
@@ -80,3 +266,76 @@ impl Display {
         Ok(collision)
     }
 }
+
+impl Snapshot for Display {
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.write_bytes(&self.memory);
+        encoder.write_bool(self.high_resolution);
+        encoder.write_u8(self.planes);
+    }
+
+    fn load(&mut self, decoder: &mut Decoder) -> Result<(), StateError> {
+        decoder.read_bytes_into(&mut self.memory)?;
+        self.high_resolution = decoder.read_bool()?;
+        self.set_planes(decoder.read_u8()?);
+        self.mark_all();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Display;
+
+    #[test]
+    fn dirty_region() {
+        let mut display = Display::new();
+
+        // A new display is dirty as a whole
+        let region = display.take_dirty_region().unwrap();
+        assert_eq!(
+            (region.x, region.y, region.width, region.height),
+            (0, 0, 64, 32)
+        );
+        assert!(region.is_row_dirty(31));
+        assert!(display.take_dirty_region().is_none());
+
+        let revision = display.get_revision();
+        assert!(!display.draw(10, 5, &[0xF0, 0x90], false, true).unwrap());
+        assert!(display.get_revision() != revision);
+
+        let region = display.take_dirty_region().unwrap();
+        assert_eq!(
+            (region.x, region.y, region.width, region.height),
+            (10, 5, 4, 2)
+        );
+        assert!(!region.is_row_dirty(4));
+        assert!(region.is_row_dirty(5) && region.is_row_dirty(6));
+        assert!(!region.is_row_dirty(7));
+
+        // Erasing the sprite changes the same area
+        assert!(display.draw(10, 5, &[0xF0, 0x90], false, true).unwrap());
+        let region = display.take_dirty_region().unwrap();
+        assert_eq!(
+            (region.x, region.y, region.width, region.height),
+            (10, 5, 4, 2)
+        );
+
+        // Nothing changes when a blank display is cleared
+        let revision = display.get_revision();
+        display.clear().unwrap();
+        assert!(!display.is_dirty());
+        assert_eq!(display.get_revision(), revision);
+
+        // Sprites that wrap around widen the region to both edges
+        display.draw(62, 0, &[0xF0], false, false).unwrap();
+        let region = display.take_dirty_region().unwrap();
+        assert_eq!((region.x, region.width), (0, 64));
+
+        display.set_high_resolution(true).unwrap();
+        let region = display.take_dirty_region().unwrap();
+        assert_eq!((region.width, region.height), (128, 64));
+        assert!(region.is_row_dirty(63));
+    }
+}
@@ -0,0 +1,31 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct DirtyRegion {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    rows: u64,
+}
+
+impl DirtyRegion {
+    pub fn new(x: usize, y: usize, width: usize, height: usize, rows: u64) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            rows,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl DirtyRegion {
+    #[wasm_bindgen]
+    pub fn is_row_dirty(&self, row: usize) -> bool {
+        row < u64::BITS as usize && self.rows & (1 << row) != 0
+    }
+}
@@ -0,0 +1,255 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::constants::{
+    AMBER_PALETTE, GREEN_PALETTE, MAX_SCALE, OCTO_PALETTE, SCANLINE_INTENSITY, WHITE_PALETTE,
+};
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Palette {
+    #[default]
+    Green,
+    Amber,
+    White,
+    Octo,
+}
+
+impl Palette {
+    pub fn colors(&self) -> [[u8; 3]; 4] {
+        match self {
+            Palette::Green => GREEN_PALETTE,
+            Palette::Amber => AMBER_PALETTE,
+            Palette::White => WHITE_PALETTE,
+            Palette::Octo => OCTO_PALETTE,
+        }
+    }
+}
+
+// Converts the display into RGBA8 pixels
+pub struct Renderer {
+    palette: Palette,
+    colors: [[u8; 3]; 4],
+    scale: usize,
+    scanlines: bool,
+    ghosting: f32,
+    // Brightness of every pixel in the previous frame, which fades out instead of turning off
+    phosphor: Vec<[f32; 3]>,
+    // Whether a pixel was still visibly fading out at the start of the frame, so it has to be
+    // rendered once more after reaching its color
+    fading: bool,
+    output: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        Self {
+            palette: Palette::default(),
+            colors: Palette::default().colors(),
+            scale: 1,
+            scanlines: false,
+            ghosting: 0.0,
+            phosphor: Vec::new(),
+            fading: false,
+            output: Vec::new(),
+            width: 0,
+            height: 0,
+        }
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+        self.colors = palette.colors();
+    }
+
+    // Discards the colors changed after the palette was selected
+    pub fn reset_colors(&mut self) {
+        self.colors = self.palette.colors();
+    }
+
+    pub fn set_color(&mut self, index: u8, color: u32) {
+        let [_, r, g, b] = color.to_be_bytes();
+        self.colors[(index & 0x3) as usize] = [r, g, b];
+    }
+
+    pub fn get_scale(&self) -> usize {
+        self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: usize) {
+        self.scale = scale.clamp(1, MAX_SCALE);
+    }
+
+    pub fn get_scanlines(&self) -> bool {
+        self.scanlines
+    }
+
+    pub fn set_scanlines(&mut self, scanlines: bool) {
+        self.scanlines = scanlines;
+    }
+
+    pub fn get_ghosting(&self) -> f32 {
+        self.ghosting
+    }
+
+    pub fn set_ghosting(&mut self, ghosting: f32) {
+        self.ghosting = ghosting.clamp(0.0, 1.0);
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_fading(&self) -> bool {
+        self.fading
+    }
+
+    // Advances the fade of the pixels that turned off by one frame
+    pub fn fade(&mut self, pixels: &[u8]) {
+        if self.phosphor.len() != pixels.len() {
+            self.phosphor = vec![[0.0; 3]; pixels.len()];
+        }
+
+        self.fading = false;
+
+        for (pixel, glow) in pixels.iter().zip(self.phosphor.iter_mut()) {
+            let target = self.colors[(pixel & 0x3) as usize];
+
+            for (channel, value) in glow.iter_mut().enumerate() {
+                self.fading |= *value as u8 != target[channel];
+                *value = (target[channel] as f32).max(*value * self.ghosting);
+            }
+        }
+    }
+
+    pub fn render(&mut self, pixels: &[u8], width: usize, height: usize) -> &[u8] {
+        let scale = self.scale;
+
+        self.width = width * scale;
+        self.height = height * scale;
+        self.output.resize(self.width * self.height * 4, 0);
+
+        if self.phosphor.len() != width * height {
+            self.phosphor = vec![[0.0; 3]; width * height];
+        }
+
+        for y in 0..height {
+            for x in 0..width {
+                let target = self.colors[(pixels[x + y * width] & 0x3) as usize];
+                let phosphor = self.phosphor[x + y * width];
+                let glow: [f32; 3] =
+                    std::array::from_fn(|channel| (target[channel] as f32).max(phosphor[channel]));
+
+                for sy in 0..scale {
+                    // Every other line is dimmed when a pixel spans several lines
+                    let intensity = if self.scanlines && scale > 1 && sy % 2 == 1 {
+                        SCANLINE_INTENSITY
+                    } else {
+                        1.0
+                    };
+
+                    let row = (y * scale + sy) * self.width;
+
+                    for sx in 0..scale {
+                        let offset = (row + x * scale + sx) * 4;
+
+                        for (channel, value) in glow.iter().enumerate() {
+                            self.output[offset + channel] = (value * intensity) as u8;
+                        }
+
+                        self.output[offset + 3] = 0xFF;
+                    }
+                }
+            }
+        }
+
+        &self.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Palette, Renderer};
+    use crate::display::constants::{MAX_SCALE, OCTO_PALETTE};
+
+    #[test]
+    fn colors() {
+        let mut renderer = Renderer::new();
+        renderer.set_palette(Palette::Octo);
+
+        let output = renderer.render(&[0, 1, 2, 3], 4, 1).to_vec();
+        assert_eq!((renderer.get_width(), renderer.get_height()), (4, 1));
+
+        for (pixel, color) in output.chunks(4).zip(OCTO_PALETTE) {
+            assert_eq!(pixel[..3], color);
+            assert_eq!(pixel[3], 0xFF);
+        }
+
+        // Only the two bits of the planes select a color
+        renderer.set_color(5, 0x123456);
+        assert_eq!(renderer.render(&[0x5], 1, 1), [0x12, 0x34, 0x56, 0xFF]);
+
+        renderer.reset_colors();
+        assert_eq!(renderer.render(&[1], 1, 1)[..3], OCTO_PALETTE[1]);
+    }
+
+    #[test]
+    fn scale() {
+        let mut renderer = Renderer::new();
+        renderer.set_color(1, 0xFFFFFF);
+
+        renderer.set_scale(0);
+        assert_eq!(renderer.get_scale(), 1);
+        renderer.set_scale(100);
+        assert_eq!(renderer.get_scale(), MAX_SCALE);
+
+        renderer.set_scale(2);
+        renderer.set_scanlines(true);
+        let output = renderer.render(&[1, 0], 2, 1).to_vec();
+        assert_eq!((renderer.get_width(), renderer.get_height()), (4, 2));
+
+        // Every second line of a pixel is dimmed
+        assert_eq!(output[..8], [255, 255, 255, 255, 255, 255, 255, 255]);
+        assert_eq!(output[8..16], [0, 0, 0, 255, 0, 0, 0, 255]);
+        assert_eq!(output[16..24], [153, 153, 153, 255, 153, 153, 153, 255]);
+
+        // which needs more than one line per pixel
+        renderer.set_scale(1);
+        assert_eq!(renderer.render(&[1], 1, 1), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn ghosting() {
+        let mut renderer = Renderer::new();
+        renderer.set_color(0, 0x000000);
+        renderer.set_color(1, 0xFFFFFF);
+        renderer.set_ghosting(0.5);
+
+        renderer.fade(&[1]);
+        renderer.fade(&[1]);
+        assert!(!renderer.is_fading());
+        renderer.fade(&[0]);
+        assert!(renderer.is_fading());
+
+        // Rendering does not advance the fade, only frames do
+        assert_eq!(renderer.render(&[0], 1, 1), [127, 127, 127, 255]);
+        assert_eq!(renderer.render(&[0], 1, 1), [127, 127, 127, 255]);
+        renderer.fade(&[0]);
+        assert_eq!(renderer.render(&[0], 1, 1), [63, 63, 63, 255]);
+
+        for _ in 0..6 {
+            renderer.fade(&[0]);
+        }
+
+        // The frame that reaches the color is still rendered
+        assert!(renderer.is_fading());
+        assert_eq!(renderer.render(&[0], 1, 1), [0, 0, 0, 255]);
+        renderer.fade(&[0]);
+        assert!(!renderer.is_fading());
+    }
+}
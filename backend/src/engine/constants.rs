@@ -1,2 +1,14 @@
 pub const MEMORY_SIZE: usize = 0x1000;
+pub const XO_CHIP_MEMORY_SIZE: usize = 0x10000;
 pub const START_ADDRESS: usize = 0x200;
+pub const FLAG_COUNT: usize = 16;
+pub const AUDIO_PATTERN_SIZE: usize = 16;
+pub const DEFAULT_PITCH: u8 = 64;
+pub const DEFAULT_INSTRUCTIONS_PER_FRAME: u32 = 12;
+pub const DEFAULT_SEED: u32 = 42;
+// The VIP runs at 1.7609 MHz and every 1802 machine cycle takes 8 clock cycles
+pub const VIP_CYCLES_PER_FRAME: u32 = 3668;
+// Cycles of every frame taken by the display interrupt and its DMA transfer
+pub const VIP_INTERRUPT_CYCLES: u32 = 1832;
+pub const VIP_FETCH_CYCLES: u32 = 40;
+pub const VIP_CLEAR_CYCLES: u32 = 3078;
@@ -1,27 +1,150 @@
+use std::fmt;
+
 use crate::display::errors::DisplayError;
-use crate::error::ErrorTrait;
 use crate::input::errors::InputError;
+use crate::movie::errors::MovieError;
+use crate::state::errors::StateError;
 
+#[derive(Debug)]
 pub enum EngineError {
-    RomTooLarge { size: usize },
-    OpCodeNotFound { op_code: u8 },
+    RomTooLarge {
+        size: usize,
+    },
+    OpCodeNotFound {
+        op_code: u16,
+        pc: u16,
+    },
+    StackOverflow {
+        op_code: u16,
+        pc: u16,
+    },
+    StackUnderflow {
+        op_code: u16,
+        pc: u16,
+    },
+    MemoryOutOfBounds {
+        address: usize,
+        op_code: u16,
+        pc: u16,
+    },
+    PcOutOfBounds {
+        pc: u16,
+    },
+    InvalidAddress {
+        address: usize,
+    },
+    InvalidRegister {
+        register: u8,
+    },
 
     DisplayError(DisplayError),
     InputError(InputError),
+    StateError(StateError),
+    MovieError(MovieError),
+}
+
+impl EngineError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EngineError::RomTooLarge { .. } => "RomTooLarge",
+            EngineError::OpCodeNotFound { .. } => "OpCodeNotFound",
+            EngineError::StackOverflow { .. } => "StackOverflow",
+            EngineError::StackUnderflow { .. } => "StackUnderflow",
+            EngineError::MemoryOutOfBounds { .. } => "MemoryOutOfBounds",
+            EngineError::PcOutOfBounds { .. } => "PcOutOfBounds",
+            EngineError::InvalidAddress { .. } => "InvalidAddress",
+            EngineError::InvalidRegister { .. } => "InvalidRegister",
+
+            EngineError::DisplayError(e) => e.kind(),
+            EngineError::InputError(e) => e.kind(),
+            EngineError::StateError(e) => e.kind(),
+            EngineError::MovieError(e) => e.kind(),
+        }
+    }
+
+    pub fn pc(&self) -> Option<u16> {
+        match self {
+            EngineError::OpCodeNotFound { pc, .. }
+            | EngineError::StackOverflow { pc, .. }
+            | EngineError::StackUnderflow { pc, .. }
+            | EngineError::MemoryOutOfBounds { pc, .. }
+            | EngineError::PcOutOfBounds { pc } => Some(*pc),
+
+            _ => None,
+        }
+    }
+
+    pub fn op_code(&self) -> Option<u16> {
+        match self {
+            EngineError::OpCodeNotFound { op_code, .. }
+            | EngineError::StackOverflow { op_code, .. }
+            | EngineError::StackUnderflow { op_code, .. }
+            | EngineError::MemoryOutOfBounds { op_code, .. } => Some(*op_code),
+
+            _ => None,
+        }
+    }
 }
 
-impl ErrorTrait for EngineError {
-    fn to_string(&self) -> String {
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             EngineError::RomTooLarge { size } => {
-                format!("ROM size {} exceeds maximum allowed size", size)
+                write!(f, "ROM size {} exceeds maximum allowed size", size)
             },
-            EngineError::OpCodeNotFound { op_code } => {
-                format!("OpCode {:#06X} not found", op_code)
+            EngineError::OpCodeNotFound { op_code, pc } => {
+                write!(f, "OpCode {:#06X} not found at {:#06X}", op_code, pc)
+            },
+            EngineError::StackOverflow { op_code, pc } => {
+                write!(
+                    f,
+                    "Stack overflow executing {:#06X} at {:#06X}",
+                    op_code, pc
+                )
+            },
+            EngineError::StackUnderflow { op_code, pc } => {
+                write!(
+                    f,
+                    "Stack underflow executing {:#06X} at {:#06X}",
+                    op_code, pc
+                )
+            },
+            EngineError::MemoryOutOfBounds {
+                address,
+                op_code,
+                pc,
+            } => write!(
+                f,
+                "Memory address {:#06X} out of bounds executing {:#06X} at {:#06X}",
+                address, op_code, pc
+            ),
+            EngineError::PcOutOfBounds { pc } => {
+                write!(f, "Program counter {:#06X} out of bounds", pc)
+            },
+            EngineError::InvalidAddress { address } => {
+                write!(f, "Memory address {:#06X} out of bounds", address)
+            },
+            EngineError::InvalidRegister { register } => {
+                write!(f, "Register V{:X} does not exist", register)
             },
 
-            EngineError::DisplayError(e) => e.to_string(),
-            EngineError::InputError(e) => e.to_string(),
+            EngineError::DisplayError(_) => write!(f, "Display error"),
+            EngineError::InputError(_) => write!(f, "Input error"),
+            EngineError::StateError(_) => write!(f, "Save state error"),
+            EngineError::MovieError(_) => write!(f, "Movie error"),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EngineError::DisplayError(e) => Some(e),
+            EngineError::InputError(e) => Some(e),
+            EngineError::StateError(e) => Some(e),
+            EngineError::MovieError(e) => Some(e),
+
+            _ => None,
         }
     }
 }
@@ -37,3 +160,15 @@ impl From<InputError> for EngineError {
         EngineError::InputError(err)
     }
 }
+
+impl From<StateError> for EngineError {
+    fn from(err: StateError) -> Self {
+        EngineError::StateError(err)
+    }
+}
+
+impl From<MovieError> for EngineError {
+    fn from(err: MovieError) -> Self {
+        EngineError::MovieError(err)
+    }
+}
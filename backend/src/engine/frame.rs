@@ -0,0 +1,11 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct FrameSummary {
+    pub instructions: u32,
+    pub display_changed: bool,
+    pub sound_active: bool,
+    pub sound_toggled: bool,
+    pub waiting_for_key: bool,
+}
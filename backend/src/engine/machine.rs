@@ -0,0 +1,18 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Debug)]
+pub struct MachineState {
+    pub registers: Vec<u8>,
+    pub index: u16,
+    pub pc: u16,
+    pub sp: u8,
+    pub stack: Vec<u16>,
+    pub delay_timer: u8,
+    pub sound_timer: u8,
+    pub flags: Vec<u8>,
+    pub pitch: u8,
+    pub planes: u8,
+    pub cycles: u64,
+    pub halted: bool,
+}
@@ -1,78 +1,233 @@
-pub use crate::display::constants::{HEIGHT, WIDTH};
-use crate::display::{Display, constants::FONT_SET};
+use std::ops::Range;
+
+use crate::audio::Audio;
+use crate::debugger::{Access, Debugger, StopKind, StopReason};
+use crate::disasm::instruction::Instruction;
+use crate::disasm::{self, DisassembledLine, Syntax};
+use crate::display::Display;
+use crate::display::constants::{FONT_SET, LARGE_FONT_ADDRESS, LARGE_FONT_SET};
+use crate::display::region::DirtyRegion;
 use crate::input::Input;
+use crate::movie::errors::MovieError;
+use crate::movie::{KeyEvent, Movie, Recorder};
+use crate::state::constants::{
+    CPU_SECTION, DISPLAY_SECTION, INPUT_SECTION, MEMORY_SECTION, QUIRKS_SECTION, RANDOM_SECTION,
+    SEED_SECTION,
+};
+use crate::state::crc::crc32;
+use crate::state::errors::StateError;
+use crate::state::{Decoder, Encoder, Snapshot, read_state, write_state};
+use crate::symbols::SymbolMap;
+use crate::trace::{CpuState, Tracer};
 
-use constants::{MEMORY_SIZE, START_ADDRESS};
+use constants::{
+    AUDIO_PATTERN_SIZE, DEFAULT_INSTRUCTIONS_PER_FRAME, DEFAULT_PITCH, DEFAULT_SEED, FLAG_COUNT,
+    START_ADDRESS, VIP_CYCLES_PER_FRAME, VIP_INTERRUPT_CYCLES,
+};
 use errors::EngineError;
-use random::MultiplyWithCarry;
+use frame::FrameSummary;
+use machine::MachineState;
+use quirks::{Platform, Quirks};
+use random::{Generator, RandomSource};
+use timing::Timing;
 
-mod constants;
+pub mod constants;
 pub mod errors;
-mod random;
+pub mod frame;
+pub mod machine;
+pub mod quirks;
+pub mod random;
+pub mod timing;
 
 pub struct Engine {
     registers: [u8; 16],
     index: u16,
     pc: u16,
-    memory: [u8; MEMORY_SIZE],
+    memory: Vec<u8>,
     stack: [u16; 16],
     sp: u8,
     delay_timer: u8,
     sound_timer: u8,
     input: Input,
     display: Display,
-    random: MultiplyWithCarry,
+    random: Box<dyn RandomSource>,
+    seed: u32,
+    platform: Platform,
+    quirks: Quirks,
+    flags: [u8; FLAG_COUNT],
+    audio_pattern: [u8; AUDIO_PATTERN_SIZE],
+    pitch: u8,
+    waiting_vblank: bool,
+    waiting_key: bool,
+    halted: bool,
+    cycles: u64,
+    frames: u64,
+    instructions_per_frame: u32,
+    timing: Timing,
+    machine_cycles: u64,
+    cycle_budget: i32,
+    debugger: Debugger,
+    symbols: SymbolMap,
+    tracer: Tracer,
+    audio: Audio,
+    rom: Vec<u8>,
+    recorder: Recorder,
 }
 
 impl Engine {
-    pub fn new() -> Self {
+    pub fn new(platform: Platform, quirks: Quirks) -> Self {
         let mut engine = Self {
             registers: [0; 16],
             index: 0,
             pc: START_ADDRESS as u16,
-            memory: [0; MEMORY_SIZE],
+            memory: vec![0; platform.memory_size()],
             stack: [0; 16],
             sp: 0,
             delay_timer: 0,
             sound_timer: 0,
             input: Input::new(),
             display: Display::new(),
-            random: random::MultiplyWithCarry::new(42),
+            random: Generator::default().create(DEFAULT_SEED),
+            seed: DEFAULT_SEED,
+            platform,
+            quirks,
+            flags: [0; FLAG_COUNT],
+            audio_pattern: [0; AUDIO_PATTERN_SIZE],
+            pitch: DEFAULT_PITCH,
+            waiting_vblank: false,
+            waiting_key: false,
+            halted: false,
+            cycles: 0,
+            frames: 0,
+            instructions_per_frame: DEFAULT_INSTRUCTIONS_PER_FRAME,
+            timing: Timing::default(),
+            machine_cycles: 0,
+            cycle_budget: 0,
+            debugger: Debugger::new(),
+            symbols: SymbolMap::new(),
+            tracer: Tracer::new(),
+            audio: Audio::new(),
+            rom: Vec::new(),
+            recorder: Recorder::default(),
         };
 
         for (i, byte) in FONT_SET.iter().enumerate() {
             engine.memory[i] = *byte;
         }
 
+        for (i, byte) in LARGE_FONT_SET.iter().enumerate() {
+            engine.memory[LARGE_FONT_ADDRESS + i] = *byte;
+        }
+
         engine
     }
 
+    fn fetch_opcode(&self, address: u16) -> Result<u16, EngineError> {
+        let address = address as usize;
+
+        if address + 1 >= self.memory.len() {
+            Err(EngineError::PcOutOfBounds { pc: address as u16 })?;
+        }
+
+        Ok(((self.memory[address] as u16) << 8) | (self.memory[address + 1] as u16))
+    }
+
+    fn memory_range(
+        &mut self,
+        address: u16,
+        length: usize,
+        access: Access,
+        pc: u16,
+        op_code: u16,
+    ) -> Result<Range<usize>, EngineError> {
+        let start = address as usize;
+        let end = start + length;
+
+        if end > self.memory.len() {
+            Err(EngineError::MemoryOutOfBounds {
+                address: end - 1,
+                op_code,
+                pc,
+            })?;
+        }
+
+        let range = start..end;
+        self.debugger.on_access(&range, access);
+
+        Ok(range)
+    }
+
+    fn skip_instruction(&mut self) -> Result<(), EngineError> {
+        let long = self.platform.supports_xo_chip() && self.fetch_opcode(self.pc)? == 0xF000;
+
+        self.pc = self.pc.wrapping_add(if long { 4 } else { 2 });
+
+        Ok(())
+    }
+
     fn decode_opcode(&mut self, opcode: u16) -> Result<(), EngineError> {
         let prefix: u8 = ((opcode & 0xF000) >> 12) as u8;
         let register_x: u8 = ((opcode & 0x0F00) >> 8) as u8;
         let register_y: u8 = ((opcode & 0x00F0) >> 4) as u8;
         let operation: u8 = (opcode & 0x000F) as u8;
 
-        self.pc += 2;
+        let pc = self.pc;
+        self.pc = self.pc.wrapping_add(2);
 
         match prefix {
             0x0 => match (register_y, operation) {
+                // 00CN | SCD N | Scrolls the display down by N pixels
+                (0xC, rows) if self.platform.supports_schip() => {
+                    self.display.scroll_down(rows as usize)?
+                },
+                // 00DN | SCU N | Scrolls the display up by N pixels
+                (0xD, rows) if self.platform.supports_xo_chip() => {
+                    self.display.scroll_up(rows as usize)?
+                },
                 // 00E0 | CLS | Clears the screen
                 (0xE, 0x0) => self.display.clear()?,
                 // 00EE | RET | Returns from a subroutine
                 (0xE, 0xE) => {
-                    self.sp -= 1;
+                    self.sp = self.sp.checked_sub(1).ok_or(EngineError::StackUnderflow {
+                        op_code: opcode,
+                        pc,
+                    })?;
                     self.pc = self.stack[self.sp as usize];
                 },
+                // 00FB | SCR | Scrolls the display right by 4 pixels
+                (0xF, 0xB) if self.platform.supports_schip() => self.display.scroll_right(4)?,
+                // 00FC | SCL | Scrolls the display left by 4 pixels
+                (0xF, 0xC) if self.platform.supports_schip() => self.display.scroll_left(4)?,
+                // 00FD | EXIT | Exits the interpreter
+                (0xF, 0xD) if self.platform.supports_schip() => {
+                    self.pc = pc;
+                    self.halted = true;
+                },
+                // 00FE | LOW | Switches to low resolution mode (64x32)
+                (0xF, 0xE) if self.platform.supports_schip() => {
+                    self.display.set_high_resolution(false)?
+                },
+                // 00FF | HIGH | Switches to high resolution mode (128x64)
+                (0xF, 0xF) if self.platform.supports_schip() => {
+                    self.display.set_high_resolution(true)?
+                },
 
                 _ => Err(EngineError::OpCodeNotFound {
-                    op_code: opcode as u8,
+                    op_code: opcode,
+                    pc,
                 })?,
             },
             // 1NNN | JP | Jumps to address NNN
             0x1 => self.pc = opcode & 0x0FFF,
             // 2NNN | CALL | Calls subroutine at NNN
             0x2 => {
+                if self.sp as usize >= self.stack.len() {
+                    Err(EngineError::StackOverflow {
+                        op_code: opcode,
+                        pc,
+                    })?;
+                }
+
                 self.stack[self.sp as usize] = self.pc;
                 self.sp += 1;
                 self.pc = opcode & 0x0FFF;
@@ -80,20 +235,57 @@ impl Engine {
             // 3XNN | SE VX NN | Skips the next instruction if VX == NN
             0x3 => {
                 if self.registers[register_x as usize] == (opcode & 0x00FF) as u8 {
-                    self.pc += 2;
+                    self.skip_instruction()?;
                 }
             },
             // 4XNN | SNE VX NN | Skips the next instruction if VX != NN
             0x4 => {
                 if self.registers[register_x as usize] != (opcode & 0x00FF) as u8 {
-                    self.pc += 2;
+                    self.skip_instruction()?;
                 }
             },
-            // 5XY0 | SE VX VY | Skips the next instruction if VX == VY
-            0x5 => {
-                if self.registers[register_x as usize] == self.registers[register_y as usize] {
-                    self.pc += 2;
-                }
+            0x5 => match operation {
+                // 5XY0 | SE VX VY | Skips the next instruction if VX == VY
+                0x0 => {
+                    if self.registers[register_x as usize] == self.registers[register_y as usize] {
+                        self.skip_instruction()?;
+                    }
+                },
+                // 5XY2 | LD [I], VX-VY | Stores from VX to VY in memory, starting at address I
+                0x2 if self.platform.supports_xo_chip() => {
+                    let registers = register_range(register_x, register_y);
+                    let range = self.memory_range(
+                        self.index,
+                        register_x.abs_diff(register_y) as usize + 1,
+                        Access::Write,
+                        pc,
+                        opcode,
+                    )?;
+
+                    for (address, register) in range.zip(registers) {
+                        self.memory[address] = self.registers[register];
+                    }
+                },
+                // 5XY3 | LD VX-VY, [I] | Fills from VX to VY with values from memory, starting at address I
+                0x3 if self.platform.supports_xo_chip() => {
+                    let registers = register_range(register_x, register_y);
+                    let range = self.memory_range(
+                        self.index,
+                        register_x.abs_diff(register_y) as usize + 1,
+                        Access::Read,
+                        pc,
+                        opcode,
+                    )?;
+
+                    for (address, register) in range.zip(registers) {
+                        self.registers[register] = self.memory[address];
+                    }
+                },
+
+                _ => Err(EngineError::OpCodeNotFound {
+                    op_code: opcode,
+                    pc,
+                })?,
             },
             // 6XNN | LD VX | Sets VX to NN
             0x6 => self.registers[register_x as usize] = (opcode & 0x00FF) as u8,
@@ -108,72 +300,130 @@ impl Engine {
                 // 8XY0 | LD VX, VY | Sets VX to the value of VY
                 0x0 => self.registers[register_x as usize] = self.registers[register_y as usize],
                 // 8XY1 | OR VX, VY | Sets VX to VX OR VY
-                0x1 => self.registers[register_x as usize] |= self.registers[register_y as usize],
+                0x1 => {
+                    self.registers[register_x as usize] |= self.registers[register_y as usize];
+
+                    if self.quirks.vf_reset {
+                        self.registers[0xF] = 0;
+                    }
+                },
                 // 8XY2 | AND VX, VY | Sets VX to VX AND VY
-                0x2 => self.registers[register_x as usize] &= self.registers[register_y as usize],
+                0x2 => {
+                    self.registers[register_x as usize] &= self.registers[register_y as usize];
+
+                    if self.quirks.vf_reset {
+                        self.registers[0xF] = 0;
+                    }
+                },
                 // 8XY3 | XOR VX, VY | Sets VX to VX XOR VY
-                0x3 => self.registers[register_x as usize] ^= self.registers[register_y as usize],
+                0x3 => {
+                    self.registers[register_x as usize] ^= self.registers[register_y as usize];
+
+                    if self.quirks.vf_reset {
+                        self.registers[0xF] = 0;
+                    }
+                },
                 // 8XY4 | ADD VX, VY | Adds VY to VX
                 0x4 => {
                     let (result, overflow) = self.registers[register_x as usize]
                         .overflowing_add(self.registers[register_y as usize]);
-                    self.registers[0xF] = if overflow { 1 } else { 0 };
                     self.registers[register_x as usize] = result;
+                    self.registers[0xF] = if overflow { 1 } else { 0 };
                 },
                 // 8XY5 | SUB VX, VY | Subtracts VY from VX
                 0x5 => {
                     let (result, borrow) = self.registers[register_x as usize]
                         .overflowing_sub(self.registers[register_y as usize]);
-                    self.registers[0xF] = if borrow { 0 } else { 1 };
                     self.registers[register_x as usize] = result;
+                    self.registers[0xF] = if borrow { 0 } else { 1 };
                 },
-                // 8XY6 | SHR VX {, VY} | Shifts VX to the right by 1
+                // 8XY6 | SHR VX {, VY} | Shifts VX (or VY) to the right by 1
                 0x6 => {
-                    self.registers[0xF] = self.registers[register_x as usize] & 0x01;
-                    self.registers[register_x as usize] >>= 1;
+                    let value = if self.quirks.shift {
+                        self.registers[register_x as usize]
+                    } else {
+                        self.registers[register_y as usize]
+                    };
+
+                    self.registers[register_x as usize] = value >> 1;
+                    self.registers[0xF] = value & 0x01;
                 },
                 // 8XY7 | SUBN VX, VY | Sets VX to VY minus VX
                 0x7 => {
                     let (result, borrow) = self.registers[register_y as usize]
                         .overflowing_sub(self.registers[register_x as usize]);
-                    self.registers[0xF] = if borrow { 0 } else { 1 };
                     self.registers[register_x as usize] = result;
+                    self.registers[0xF] = if borrow { 0 } else { 1 };
                 },
-                // 8XYE | SHL VX {, VY} | Shifts VX to the left by 1
+                // 8XYE | SHL VX {, VY} | Shifts VX (or VY) to the left by 1
                 0xE => {
-                    // self.registers[0xF] = (self.registers[register_x as usize] & 0x80) >> 7; // TODO: Mirar a ver si es correcto
-                    self.registers[0xF] = self.registers[register_x as usize] & 0x80;
-                    self.registers[register_x as usize] <<= 1;
+                    let value = if self.quirks.shift {
+                        self.registers[register_x as usize]
+                    } else {
+                        self.registers[register_y as usize]
+                    };
+
+                    self.registers[register_x as usize] = value << 1;
+                    self.registers[0xF] = (value & 0x80) >> 7;
                 },
 
                 _ => Err(EngineError::OpCodeNotFound {
-                    op_code: opcode as u8,
+                    op_code: opcode,
+                    pc,
                 })?,
             },
-            // 9XY0 | SNE VX, VY | Skips the next instruction if VX != VY
-            0x9 => {
-                if self.registers[register_x as usize] != self.registers[register_y as usize] {
-                    self.pc += 2;
-                }
+            0x9 => match operation {
+                // 9XY0 | SNE VX, VY | Skips the next instruction if VX != VY
+                0x0 => {
+                    if self.registers[register_x as usize] != self.registers[register_y as usize] {
+                        self.skip_instruction()?;
+                    }
+                },
+
+                _ => Err(EngineError::OpCodeNotFound {
+                    op_code: opcode,
+                    pc,
+                })?,
             },
             // ANNN | LD I, NNN | Sets I to the address NNN
             0xA => self.index = opcode & 0x0FFF,
-            // BNNN | JP V0, NNN | Jumps to the address NNN + V0
-            0xB => self.pc = (opcode & 0x0FFF) + self.registers[0] as u16,
+            // BNNN | JP V0, NNN | Jumps to the address NNN + V0 (or XNN + VX)
+            0xB => {
+                let offset = if self.quirks.jump {
+                    self.registers[register_x as usize]
+                } else {
+                    self.registers[0]
+                };
+
+                self.pc = (opcode & 0x0FFF) + offset as u16;
+            },
             // CXNN | RND VX, NN | Sets VX to the result of a bitwise and operation on a random number and NN
             0xC => {
                 self.registers[register_x as usize] =
-                    self.random.random() as u8 & (opcode & 0x00FF) as u8
+                    self.random.random(&self.memory) as u8 & (opcode & 0x00FF) as u8
             },
             // DXYN | DRW VX, VY, N | Draws a sprite at coordinate (VX, VY) that has a width of 8 pixels and a height of N pixels
+            // DXY0 | DRW VX, VY, 0 | Draws a 16x16 sprite at coordinate (VX, VY)
             0xD => {
+                let wide = operation == 0 && self.platform.supports_schip();
+                let size =
+                    if wide { 32 } else { operation as usize } * self.display.get_plane_count();
+
+                let range = self.memory_range(self.index, size, Access::Read, pc, opcode)?;
+
                 let collision = self.display.draw(
                     self.registers[register_x as usize] as usize,
                     self.registers[register_y as usize] as usize,
-                    &self.memory[self.index as usize..(self.index as usize + operation as usize)], // TODO: Mirar a ver si es correcto
+                    &self.memory[range],
+                    wide,
+                    self.quirks.clipping,
                 )?;
 
                 self.registers[0xF] = if collision { 1 } else { 0 };
+
+                if self.quirks.display_wait {
+                    self.waiting_vblank = true;
+                }
             },
             0xE => match (register_y, operation) {
                 // EX9E | SKP VX | Skips the next instruction if the key stored in VX is pressed
@@ -182,7 +432,7 @@ impl Engine {
                         .input
                         .is_key_down(self.registers[register_x as usize])?
                     {
-                        self.pc += 2;
+                        self.skip_instruction()?;
                     }
                 },
                 // EXA1 | SKNP VX | Skips the next instruction if the key stored in VX is not pressed
@@ -191,91 +441,558 @@ impl Engine {
                         .input
                         .is_key_down(self.registers[register_x as usize])?
                     {
-                        self.pc += 2;
+                        self.skip_instruction()?;
                     }
                 },
 
                 _ => Err(EngineError::OpCodeNotFound {
-                    op_code: opcode as u8,
+                    op_code: opcode,
+                    pc,
                 })?,
             },
             0xF => match (register_y, operation) {
+                // F000 NNNN | LD I, NNNN | Sets I to the 16-bit address stored in the next two bytes
+                (0x0, 0x0) if register_x == 0 && self.platform.supports_xo_chip() => {
+                    self.index = self.fetch_opcode(self.pc)?;
+                    self.pc = self.pc.wrapping_add(2);
+                },
+                // FN01 | PLANE N | Selects the drawing planes given by the bitmask N
+                (0x0, 0x1) if self.platform.supports_xo_chip() => {
+                    self.display.set_planes(register_x)
+                },
+                // F002 | AUDIO | Loads 16 bytes starting at address I into the audio pattern buffer
+                (0x0, 0x2) if register_x == 0 && self.platform.supports_xo_chip() => {
+                    let range = self.memory_range(
+                        self.index,
+                        AUDIO_PATTERN_SIZE,
+                        Access::Read,
+                        pc,
+                        opcode,
+                    )?;
+                    self.audio_pattern.copy_from_slice(&self.memory[range]);
+                },
                 // FX07 | LD VX, DT | Sets VX to the value of the delay timer
                 (0x0, 0x7) => self.registers[register_x as usize] = self.delay_timer,
-                // FX0A | LD VX, N | A key press is awaited, and then stored in VX
-                (0x0, 0xA) => {
-                    self.pc -= 2;
-
-                    for (i, key) in self.input.keys.iter().enumerate() {
-                        if *key {
-                            self.registers[register_x as usize] = i as u8;
-                            self.pc += 2;
-                        }
-                    }
+                // FX0A | LD VX, N | A key press (or release) is awaited, and then stored in VX
+                (0x0, 0xA) => match self.input.wait_key(self.quirks.key_release) {
+                    Some(key) => self.registers[register_x as usize] = key,
+                    None => {
+                        self.pc = pc;
+                        self.waiting_key = true;
+                    },
                 },
                 // FX15 | LD DT, VX | Sets the delay timer to VX
                 (0x1, 0x5) => self.delay_timer = self.registers[register_x as usize],
                 // FX18 | LD ST, VX | Sets the sound timer to VX
                 (0x1, 0x8) => self.sound_timer = self.registers[register_x as usize],
                 // FX1E | ADD I, VX | Adds VX to I
-                (0x1, 0xE) => self.index += self.registers[register_x as usize] as u16,
+                (0x1, 0xE) => {
+                    self.index = self
+                        .index
+                        .wrapping_add(self.registers[register_x as usize] as u16)
+                },
                 // FX29 | LD F, VX | Sets I to the location of the sprite for the character in VX
                 (0x2, 0x9) => self.index = self.registers[register_x as usize] as u16 * 5,
+                // FX30 | LD HF, VX | Sets I to the location of the large sprite for the digit in VX
+                (0x3, 0x0) if self.platform.supports_schip() => {
+                    self.index =
+                        LARGE_FONT_ADDRESS as u16 + self.registers[register_x as usize] as u16 * 10
+                },
+                // FX3A | PITCH VX | Sets the audio pitch register to VX
+                (0x3, 0xA) if self.platform.supports_xo_chip() => {
+                    self.pitch = self.registers[register_x as usize]
+                },
                 // FX33 | LD B, VX | Stores the binary-coded decimal representation of VX in memory locations I, I+1, and I+2
                 (0x3, 0x3) => {
-                    self.memory[self.index as usize] = self.registers[register_x as usize] / 100;
-                    self.memory[self.index as usize + 1] =
-                        (self.registers[register_x as usize] / 10) % 10;
-                    self.memory[self.index as usize + 2] =
-                        (self.registers[register_x as usize] % 100) % 10;
+                    let range = self.memory_range(self.index, 3, Access::Write, pc, opcode)?;
+                    let value = self.registers[register_x as usize];
+
+                    self.memory[range].copy_from_slice(&[
+                        value / 100,
+                        (value / 10) % 10,
+                        value % 10,
+                    ]);
                 },
                 // FX55 | LD [I], VX | Stores from V0 to VX in memory, starting at address I
-                (0x5, 0x5) => self.memory
-                    [(self.index as usize)..(self.index + register_x as u16 + 1) as usize]
-                    .copy_from_slice(&self.registers[0..=register_x as usize]),
+                (0x5, 0x5) => {
+                    let range = self.memory_range(
+                        self.index,
+                        register_x as usize + 1,
+                        Access::Write,
+                        pc,
+                        opcode,
+                    )?;
+                    self.memory[range].copy_from_slice(&self.registers[0..=register_x as usize]);
+
+                    if self.quirks.memory_increment {
+                        self.index = self.index.wrapping_add(register_x as u16 + 1);
+                    }
+                },
                 // FX65 | LD VX, [I] | Fills from V0 to VX with values from memory, starting at address I
-                (0x6, 0x5) => self.registers[0..(register_x as usize + 1)].copy_from_slice(
-                    &self.memory
-                        [self.index as usize..(self.index + register_x as u16 + 1) as usize],
-                ),
+                (0x6, 0x5) => {
+                    let range = self.memory_range(
+                        self.index,
+                        register_x as usize + 1,
+                        Access::Read,
+                        pc,
+                        opcode,
+                    )?;
+                    self.registers[0..=register_x as usize].copy_from_slice(&self.memory[range]);
+
+                    if self.quirks.memory_increment {
+                        self.index = self.index.wrapping_add(register_x as u16 + 1);
+                    }
+                },
+                // FX75 | LD R, VX | Stores V0 to VX in the RPL user flags
+                (0x7, 0x5) if self.platform.supports_schip() => self.flags[0..=register_x as usize]
+                    .copy_from_slice(&self.registers[0..=register_x as usize]),
+                // FX85 | LD VX, R | Fills V0 to VX with the values of the RPL user flags
+                (0x8, 0x5) if self.platform.supports_schip() => self.registers
+                    [0..=register_x as usize]
+                    .copy_from_slice(&self.flags[0..=register_x as usize]),
 
                 _ => Err(EngineError::OpCodeNotFound {
-                    op_code: opcode as u8,
+                    op_code: opcode,
+                    pc,
                 })?,
             },
 
             _ => Err(EngineError::OpCodeNotFound {
-                op_code: opcode as u8,
+                op_code: opcode,
+                pc,
             })?,
         }
 
         Ok(())
     }
 
-    pub fn load_rom(&mut self, rom_data: &[u8]) -> Result<(), EngineError> {
-        if rom_data.len() > MEMORY_SIZE - START_ADDRESS {
-            Err(EngineError::RomTooLarge {
-                size: rom_data.len(),
-            })?;
+    pub fn check_rom_size(platform: Platform, size: usize) -> Result<(), EngineError> {
+        if size > platform.memory_size() - START_ADDRESS {
+            Err(EngineError::RomTooLarge { size })?;
         }
 
-        *self = Self::new();
+        Ok(())
+    }
+
+    pub fn load_rom(&mut self, rom_data: &[u8]) -> Result<(), EngineError> {
+        Self::check_rom_size(self.platform, rom_data.len())?;
+
+        let flags = self.flags;
+        let instructions_per_frame = self.instructions_per_frame;
+        let timing = self.timing;
+        let (seed, generator) = (self.seed, self.random.generator());
+        let debugger = std::mem::take(&mut self.debugger);
+        let tracer = std::mem::replace(&mut self.tracer, Tracer::new());
+        let audio = std::mem::replace(&mut self.audio, Audio::new());
+
+        // Symbols belong to the previous ROM, so they are dropped with it
+        *self = Self::new(self.platform, self.quirks);
+        self.flags = flags;
+        self.instructions_per_frame = instructions_per_frame;
+        self.timing = timing;
+        self.seed = seed;
+        self.random = generator.create(seed);
+        self.debugger = debugger;
+        self.tracer = tracer;
+        self.audio = audio;
+        self.audio.clear();
 
         self.memory[START_ADDRESS..(START_ADDRESS + rom_data.len())].copy_from_slice(rom_data);
+        self.rom = rom_data.to_vec();
 
         Ok(())
     }
 
+    // Loads the ROM again, keeping its symbols
+    pub fn restart(&mut self) -> Result<(), EngineError> {
+        let rom = std::mem::take(&mut self.rom);
+        let symbols = std::mem::take(&mut self.symbols);
+        let result = self.load_rom(&rom);
+        self.symbols = symbols;
+
+        result
+    }
+
     pub fn execute_cycle(&mut self) -> Result<(), EngineError> {
-        let opcode = ((self.memory[self.pc as usize] as u16) << 8)
-            | (self.memory[(self.pc + 1) as usize] as u16);
+        while let Some(event) = self.recorder.next_event(self.frames, self.cycles) {
+            self.input.set_key(event.key, event.pressed)?;
+        }
+
+        if self.waiting_vblank || self.halted {
+            return Ok(());
+        }
+
+        let pc = self.pc;
+        let opcode = self.fetch_opcode(pc)?;
+        let registers = self.registers;
+
+        if self.tracer.is_enabled() {
+            let next = self.fetch_opcode(pc.wrapping_add(2)).ok();
+            let instruction = Instruction::decode(opcode, next, self.platform);
+            let before = self.cpu_state();
+
+            let result = self.decode_opcode(opcode);
+            let after = self.cpu_state();
+
+            // An instruction that faults is recorded too, so the trace ends with it
+            self.tracer
+                .record(self.cycles + 1, pc, instruction, &before, &after);
+
+            result?;
+            self.cycles += 1;
+        } else {
+            self.decode_opcode(opcode)?;
+            self.cycles += 1;
+        }
+
+        if self.timing == Timing::Vip {
+            let skipped = self.pc != pc.wrapping_add(2);
+            let cycles = timing::vip_cycles(opcode, &registers, skipped);
 
-        self.decode_opcode(opcode)?;
+            self.machine_cycles += cycles as u64;
+            self.cycle_budget -= cycles as i32;
+        }
 
         Ok(())
     }
 
+    // Executes the instructions up to a cycle again, without tracing them or hitting watchpoints
+    pub fn replay(&mut self, target: u64) -> Result<(), EngineError> {
+        let debugger = std::mem::take(&mut self.debugger);
+        let tracing = self.tracer.is_enabled();
+        self.tracer.set_enabled(false);
+
+        let mut result = Ok(());
+
+        while self.cycles < target {
+            let cycles = self.cycles;
+            result = self.execute_cycle();
+
+            if result.is_err() || self.cycles == cycles {
+                break;
+            }
+        }
+
+        self.debugger = debugger;
+        self.tracer.set_enabled(tracing);
+
+        result
+    }
+
+    fn cpu_state(&self) -> CpuState {
+        CpuState {
+            registers: self.registers,
+            index: self.index,
+            delay_timer: self.delay_timer,
+            sound_timer: self.sound_timer,
+        }
+    }
+
+    pub fn run_frame(&mut self) -> Result<FrameSummary, EngineError> {
+        let revision = self.display.get_revision();
+        let sound_active = self.is_sound_active();
+        let mut instructions = 0;
+
+        self.waiting_key = false;
+
+        if self.timing == Timing::Vip {
+            self.cycle_budget += (VIP_CYCLES_PER_FRAME - VIP_INTERRUPT_CYCLES) as i32;
+        }
+
+        while self.has_frame_time(instructions)
+            && !self.waiting_vblank
+            && !self.waiting_key
+            && !self.halted
+        {
+            self.execute_cycle()?;
+            instructions += 1;
+        }
+
+        // Instructions that run past the end of the frame delay the next one, but the time spent
+        // waiting for the display interrupt or a key is lost
+        self.cycle_budget = self.cycle_budget.min(0);
+
+        self.decrement_timer()?;
+
+        Ok(FrameSummary {
+            instructions,
+            display_changed: self.display.get_revision() != revision,
+            sound_active: self.is_sound_active(),
+            sound_toggled: self.is_sound_active() != sound_active,
+            waiting_for_key: self.waiting_key,
+        })
+    }
+
+    fn has_frame_time(&self, instructions: u32) -> bool {
+        match self.timing {
+            Timing::Instructions => instructions < self.instructions_per_frame,
+            Timing::Vip => self.cycle_budget > 0,
+        }
+    }
+
+    pub fn get_seed(&self) -> u32 {
+        self.seed
+    }
+
+    // Restarts the random number generator from the given seed
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+        self.random = self.random.generator().create(seed);
+    }
+
+    pub fn get_generator(&self) -> Generator {
+        self.random.generator()
+    }
+
+    pub fn set_generator(&mut self, generator: Generator) {
+        self.random = generator.create(self.seed);
+    }
+
+    pub fn get_timing(&self) -> Timing {
+        self.timing
+    }
+
+    pub fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+        self.cycle_budget = 0;
+    }
+
+    pub fn get_machine_cycles(&self) -> u64 {
+        self.machine_cycles
+    }
+
+    pub fn get_instructions_per_frame(&self) -> u32 {
+        self.instructions_per_frame
+    }
+
+    pub fn set_instructions_per_frame(&mut self, instructions: u32) {
+        self.instructions_per_frame = instructions;
+    }
+
+    pub fn get_rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    pub fn run_until_break(&mut self, max_cycles: u32) -> Result<StopReason, EngineError> {
+        let mut stop = self.run(max_cycles)?;
+        stop.symbol = self.symbols.lookup(stop.pc);
+
+        Ok(stop)
+    }
+
+    fn run(&mut self, max_cycles: u32) -> Result<StopReason, EngineError> {
+        let mut stop = StopReason {
+            kind: StopKind::CycleLimit,
+            id: None,
+            pc: self.pc,
+            opcode: None,
+            address: None,
+            cycles: 0,
+            symbol: None,
+        };
+
+        while stop.cycles < max_cycles {
+            stop.pc = self.pc;
+            stop.opcode = None;
+
+            if self.halted {
+                stop.kind = StopKind::Halted;
+                return Ok(stop);
+            }
+
+            if self.waiting_vblank {
+                stop.kind = StopKind::VerticalBlank;
+                return Ok(stop);
+            }
+
+            let opcode = self.fetch_opcode(self.pc)?;
+            stop.opcode = Some(opcode);
+
+            if stop.cycles > 0
+                && let Some((kind, id)) = self.debugger.check(self, opcode)
+            {
+                stop.kind = kind;
+                stop.id = Some(id);
+                return Ok(stop);
+            }
+
+            self.debugger.take_hit();
+            self.execute_cycle()?;
+            stop.cycles += 1;
+
+            if let Some(hit) = self.debugger.take_hit() {
+                stop.kind = StopKind::Watchpoint;
+                stop.id = Some(hit.id);
+                stop.address = Some(hit.address);
+                return Ok(stop);
+            }
+        }
+
+        stop.pc = self.pc;
+        stop.opcode = self.fetch_opcode(self.pc).ok();
+
+        Ok(stop)
+    }
+
+    pub fn debugger(&mut self) -> &mut Debugger {
+        &mut self.debugger
+    }
+
+    pub fn tracer(&mut self) -> &mut Tracer {
+        &mut self.tracer
+    }
+
+    pub fn audio(&mut self) -> &mut Audio {
+        &mut self.audio
+    }
+
+    pub fn get_audio(&self) -> &Audio {
+        &self.audio
+    }
+
+    pub fn get_tracer(&self) -> &Tracer {
+        &self.tracer
+    }
+
+    pub fn get_symbols(&self) -> &SymbolMap {
+        &self.symbols
+    }
+
+    pub fn set_symbols(&mut self, symbols: SymbolMap) {
+        self.symbols = symbols;
+    }
+
+    pub fn get_stack_trace(&self) -> Vec<String> {
+        let calls = self.stack[..self.sp as usize]
+            .iter()
+            .rev()
+            .map(|address| address.wrapping_sub(2));
+
+        std::iter::once(self.pc)
+            .chain(calls)
+            .map(|address| self.symbols.format_address(address))
+            .collect()
+    }
+
+    pub fn get_register(&self, register: u8) -> u8 {
+        self.registers[register as usize & 0xF]
+    }
+
+    pub fn get_index(&self) -> u16 {
+        self.index
+    }
+
+    pub fn get_pc(&self) -> u16 {
+        self.pc
+    }
+
+    pub fn get_sp(&self) -> u8 {
+        self.sp
+    }
+
+    pub fn get_delay_timer(&self) -> u8 {
+        self.delay_timer
+    }
+
+    pub fn get_sound_timer(&self) -> u8 {
+        self.sound_timer
+    }
+
+    pub fn peek_memory(&self, address: u16) -> u8 {
+        self.memory.get(address as usize).copied().unwrap_or(0)
+    }
+
+    pub fn get_machine_state(&self) -> MachineState {
+        MachineState {
+            registers: self.registers.to_vec(),
+            index: self.index,
+            pc: self.pc,
+            sp: self.sp,
+            stack: self.stack[..self.sp as usize].to_vec(),
+            delay_timer: self.delay_timer,
+            sound_timer: self.sound_timer,
+            flags: self.flags.to_vec(),
+            pitch: self.pitch,
+            planes: self.display.get_planes(),
+            cycles: self.cycles,
+            halted: self.halted,
+        }
+    }
+
+    fn address_range(&self, address: u16, length: usize) -> Result<Range<usize>, EngineError> {
+        let start = address as usize;
+        let end = start + length;
+
+        if end > self.memory.len() {
+            Err(EngineError::InvalidAddress { address: end - 1 })?;
+        }
+
+        Ok(start..end)
+    }
+
+    pub fn disassemble(&self, address: u16, count: usize, syntax: Syntax) -> Vec<DisassembledLine> {
+        disasm::disassemble(
+            &self.memory,
+            address,
+            count,
+            self.platform,
+            syntax,
+            &self.symbols,
+        )
+    }
+
+    pub fn read_memory(&self, address: u16, length: usize) -> Result<&[u8], EngineError> {
+        Ok(&self.memory[self.address_range(address, length)?])
+    }
+
+    pub fn write_memory(&mut self, address: u16, data: &[u8]) -> Result<(), EngineError> {
+        let range = self.address_range(address, data.len())?;
+        self.memory[range].copy_from_slice(data);
+
+        Ok(())
+    }
+
+    pub fn set_register(&mut self, register: u8, value: u8) -> Result<(), EngineError> {
+        if register as usize >= self.registers.len() {
+            Err(EngineError::InvalidRegister { register })?;
+        }
+
+        self.registers[register as usize] = value;
+
+        Ok(())
+    }
+
+    pub fn set_index(&mut self, index: u16) {
+        self.index = index;
+    }
+
+    pub fn set_pc(&mut self, pc: u16) -> Result<(), EngineError> {
+        self.address_range(pc, 2)?;
+        self.pc = pc;
+        self.halted = false;
+
+        Ok(())
+    }
+
+    pub fn set_delay_timer(&mut self, value: u8) {
+        self.delay_timer = value;
+    }
+
+    pub fn set_sound_timer(&mut self, value: u8) {
+        self.sound_timer = value;
+    }
+
     pub fn decrement_timer(&mut self) -> Result<(), EngineError> {
+        self.waiting_vblank = false;
+        self.frames += 1;
+        self.input.next_frame();
+
+        // XO-CHIP programs that loaded a pattern play it instead of the default tone
+        let pattern = (self.platform.supports_xo_chip()
+            && self.audio_pattern.iter().any(|b| *b != 0))
+        .then_some((&self.audio_pattern, self.pitch));
+        self.audio.push_frame(self.sound_timer > 0, pattern);
+
         if self.delay_timer > 0 {
             self.delay_timer -= 1;
         }
@@ -287,23 +1004,698 @@ impl Engine {
         Ok(())
     }
 
-    pub fn get_display(&self) -> &[u8; WIDTH * HEIGHT] {
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut cpu = Encoder::new();
+        self.save(&mut cpu);
+
+        let mut memory = Encoder::new();
+        memory.write_bytes(&self.memory);
+
+        let mut quirks = Encoder::new();
+        self.quirks.save(&mut quirks);
+
+        let mut display = Encoder::new();
+        self.display.save(&mut display);
+
+        let mut input = Encoder::new();
+        self.input.save(&mut input);
+
+        let mut seed = Encoder::new();
+        seed.write_u8(self.random.generator() as u8);
+        seed.write_u32(self.seed);
+
+        let mut random = Encoder::new();
+        self.random.save(&mut random);
+
+        write_state(
+            self.platform,
+            &[
+                (CPU_SECTION, cpu.into_bytes()),
+                (MEMORY_SECTION, memory.into_bytes()),
+                (QUIRKS_SECTION, quirks.into_bytes()),
+                (DISPLAY_SECTION, display.into_bytes()),
+                (INPUT_SECTION, input.into_bytes()),
+                (SEED_SECTION, seed.into_bytes()),
+                (RANDOM_SECTION, random.into_bytes()),
+            ],
+        )
+    }
+
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), EngineError> {
+        let sections = read_state(data, self.platform)?;
+        let mut engine = Self::new(self.platform, self.quirks);
+
+        sections.load(CPU_SECTION, &mut engine)?;
+        sections
+            .get(MEMORY_SECTION)?
+            .read_bytes_into(&mut engine.memory)
+            .map_err(|_| StateError::InvalidSection {
+                tag: MEMORY_SECTION,
+            })?;
+        sections.load(QUIRKS_SECTION, &mut engine.quirks)?;
+        sections.load(DISPLAY_SECTION, &mut engine.display)?;
+        sections.load(INPUT_SECTION, &mut engine.input)?;
+
+        let mut decoder = sections.get(SEED_SECTION)?;
+        let invalid = |_| StateError::InvalidSection { tag: SEED_SECTION };
+        let generator = decoder.read_u8().map_err(invalid)?;
+        engine.seed = decoder.read_u32().map_err(invalid)?;
+        engine.random = Generator::try_from(generator)
+            .map_err(|_| StateError::InvalidSection { tag: SEED_SECTION })?
+            .create(engine.seed);
+
+        if !decoder.is_empty() {
+            Err(StateError::InvalidSection { tag: SEED_SECTION })?;
+        }
+
+        sections.load(RANDOM_SECTION, engine.random.as_mut())?;
+
+        engine.instructions_per_frame = self.instructions_per_frame;
+        engine.timing = self.timing;
+        engine.debugger = std::mem::take(&mut self.debugger);
+        engine.symbols = std::mem::take(&mut self.symbols);
+        engine.tracer = std::mem::replace(&mut self.tracer, Tracer::new());
+        engine.audio = std::mem::replace(&mut self.audio, Audio::new());
+        engine.audio.clear();
+        engine.rom = std::mem::take(&mut self.rom);
+        *self = engine;
+
+        Ok(())
+    }
+
+    pub fn get_display(&self) -> &[u8] {
         self.display.get_memory()
     }
 
+    pub fn is_display_dirty(&self) -> bool {
+        self.display.is_dirty()
+    }
+
+    pub fn take_dirty_region(&mut self) -> Option<DirtyRegion> {
+        self.display.take_dirty_region()
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.display.get_width()
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.display.get_height()
+    }
+
+    pub fn get_cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    pub fn get_frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
     pub fn is_sound_active(&self) -> bool {
         self.sound_timer > 0
     }
 
+    pub fn get_quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+    }
+
+    pub fn get_platform(&self) -> Platform {
+        self.platform
+    }
+
+    pub fn set_platform(&mut self, platform: Platform) {
+        self.platform = platform;
+        self.quirks = Quirks::new(platform);
+        self.memory.resize(platform.memory_size(), 0);
+    }
+
+    pub fn get_planes(&self) -> u8 {
+        self.display.get_planes()
+    }
+
+    pub fn get_audio_pattern(&self) -> &[u8; AUDIO_PATTERN_SIZE] {
+        &self.audio_pattern
+    }
+
+    pub fn get_pitch(&self) -> u8 {
+        self.pitch
+    }
+
     pub fn key_down(&mut self, key: u8) -> Result<(), EngineError> {
-        self.input.key_down(key)?;
+        self.set_key(key, true)
+    }
+
+    pub fn key_up(&mut self, key: u8) -> Result<(), EngineError> {
+        self.set_key(key, false)
+    }
+
+    fn set_key(&mut self, key: u8, pressed: bool) -> Result<(), EngineError> {
+        // The keypad is driven by the movie while it plays
+        if self.recorder.is_playing() {
+            return Ok(());
+        }
+
+        if self.input.is_key_down(key)? != pressed {
+            self.recorder.record(KeyEvent {
+                frame: self.frames,
+                cycle: self.cycles,
+                key,
+                pressed,
+            });
+        }
+
+        self.input.set_key(key, pressed)?;
 
         Ok(())
     }
 
-    pub fn key_up(&mut self, key: u8) -> Result<(), EngineError> {
-        self.input.key_up(key)?;
+    // Restarts the loaded ROM and records every key transition from then on
+    pub fn start_recording(&mut self) -> Result<(), EngineError> {
+        self.restart()?;
+
+        self.recorder = Recorder::Recording(Movie {
+            platform: self.platform,
+            quirks: self.quirks,
+            generator: self.random.generator(),
+            seed: self.seed,
+            timing: self.timing,
+            instructions_per_frame: self.instructions_per_frame,
+            flags: self.flags,
+            rom_hash: crc32(&self.rom),
+            events: Vec::new(),
+        });
 
         Ok(())
     }
+
+    pub fn stop_recording(&mut self) -> Option<Vec<u8>> {
+        if !self.recorder.is_recording() {
+            return None;
+        }
+
+        self.recorder.stop().map(|movie| movie.encode())
+    }
+
+    // Restarts the loaded ROM with the configuration of the movie and replays its key transitions
+    pub fn play_movie(&mut self, data: &[u8]) -> Result<(), EngineError> {
+        let movie = Movie::decode(data)?;
+        let found = crc32(&self.rom);
+
+        if movie.rom_hash != found {
+            Err(MovieError::RomMismatch {
+                expected: movie.rom_hash,
+                found,
+            })?;
+        }
+
+        self.set_platform(movie.platform);
+        self.quirks = movie.quirks;
+        self.set_generator(movie.generator);
+        self.set_seed(movie.seed);
+        self.timing = movie.timing;
+        self.instructions_per_frame = movie.instructions_per_frame;
+        self.flags = movie.flags;
+
+        self.restart()?;
+
+        self.recorder = Recorder::Playing { movie, position: 0 };
+
+        Ok(())
+    }
+
+    pub fn stop_playback(&mut self) {
+        if self.recorder.is_playing() {
+            self.recorder.stop();
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_recording()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.recorder.is_playing()
+    }
+}
+
+fn register_range(start: u8, end: u8) -> Box<dyn Iterator<Item = usize>> {
+    if start <= end {
+        Box::new(start as usize..=end as usize)
+    } else {
+        Box::new((end as usize..=start as usize).rev())
+    }
+}
+
+impl Snapshot for Engine {
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.write_bytes(&self.registers);
+        encoder.write_u16(self.index);
+        encoder.write_u16(self.pc);
+
+        for address in self.stack {
+            encoder.write_u16(address);
+        }
+
+        encoder.write_u8(self.sp);
+        encoder.write_u8(self.delay_timer);
+        encoder.write_u8(self.sound_timer);
+        encoder.write_bytes(&self.flags);
+        encoder.write_bytes(&self.audio_pattern);
+        encoder.write_u8(self.pitch);
+        encoder.write_bool(self.waiting_vblank);
+        encoder.write_bool(self.halted);
+        encoder.write_u64(self.cycles);
+        encoder.write_u64(self.machine_cycles);
+        encoder.write_u32(self.cycle_budget as u32);
+        encoder.write_u64(self.frames);
+    }
+
+    fn load(&mut self, decoder: &mut Decoder) -> Result<(), StateError> {
+        decoder.read_bytes_into(&mut self.registers)?;
+        self.index = decoder.read_u16()?;
+        self.pc = decoder.read_u16()?;
+
+        for address in self.stack.iter_mut() {
+            *address = decoder.read_u16()?;
+        }
+
+        self.sp = decoder.read_u8()?;
+        self.delay_timer = decoder.read_u8()?;
+        self.sound_timer = decoder.read_u8()?;
+        decoder.read_bytes_into(&mut self.flags)?;
+        decoder.read_bytes_into(&mut self.audio_pattern)?;
+        self.pitch = decoder.read_u8()?;
+        self.waiting_vblank = decoder.read_bool()?;
+        self.halted = decoder.read_bool()?;
+
+        self.cycles = decoder.read_u64()?;
+        self.machine_cycles = decoder.read_u64()?;
+        self.cycle_budget = decoder.read_u32()? as i32;
+        self.frames = decoder.read_u64()?;
+
+        if self.sp as usize > self.stack.len() {
+            Err(StateError::InvalidSection { tag: CPU_SECTION })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Engine;
+    use super::constants::DEFAULT_INSTRUCTIONS_PER_FRAME;
+    use super::errors::EngineError;
+    use crate::disasm::Syntax;
+    use crate::disasm::instruction::Instruction;
+    use crate::display::constants::LARGE_FONT_ADDRESS;
+    use crate::engine::quirks::{Platform, Quirks};
+
+    // Counts in V0, draws a random sprite and waits on the delay timer
+    const ROM: [u8; 18] = [
+        0x70, 0x01, 0xC1, 0xFF, 0xA2, 0x10, 0xD0, 0x15, 0x62, 0x03, 0xF2, 0x15, 0x12, 0x00, 0x00,
+        0x00, 0xF0, 0x90,
+    ];
+
+    fn engine() -> Engine {
+        let mut engine = Engine::new(Platform::XoChip, Quirks::new(Platform::XoChip));
+        engine.load_rom(&ROM).unwrap();
+        engine
+    }
+
+    fn run(engine: &mut Engine, frames: usize) {
+        for _ in 0..frames {
+            engine.run_frame().unwrap();
+        }
+    }
+
+    fn load(platform: Platform, rom: &[u8]) -> Engine {
+        let mut engine = Engine::new(platform, Quirks::new(platform));
+        engine.load_rom(rom).unwrap();
+        engine
+    }
+
+    fn step(engine: &mut Engine, cycles: usize) {
+        for _ in 0..cycles {
+            engine.execute_cycle().unwrap();
+        }
+    }
+
+    #[test]
+    fn save_state_round_trip() {
+        let mut original = engine();
+        run(&mut original, 10);
+        let state = original.save_state();
+
+        let mut restored = engine();
+        restored.load_state(&state).unwrap();
+        assert_eq!(restored.save_state(), state);
+
+        // Both engines continue identically, including the random numbers
+        run(&mut original, 10);
+        run(&mut restored, 10);
+        assert_eq!(restored.save_state(), original.save_state());
+    }
+
+    #[test]
+    fn trace_faulting_instruction() {
+        let mut engine = Engine::new(Platform::Chip48, Quirks::new(Platform::Chip48));
+        engine.load_rom(&[0x60, 0x01, 0xFF, 0xFF]).unwrap();
+        engine.tracer().set_enabled(true);
+
+        engine.execute_cycle().unwrap();
+        assert!(engine.execute_cycle().is_err());
+
+        let trace = engine.get_tracer().export_text(Syntax::Classic);
+        let lines: Vec<&str> = trace.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains("0202 FFFF"), "{}", lines[1]);
+    }
+
+    #[test]
+    fn load_state_platform_mismatch() {
+        let state = engine().save_state();
+        let mut other = Engine::new(Platform::Vip, Quirks::new(Platform::Vip));
+
+        assert!(other.load_state(&state).is_err());
+    }
+
+    #[test]
+    fn unknown_opcodes() {
+        // The engine faults on exactly the opcodes that the disassembler shows as data
+        for platform in [Platform::Vip, Platform::Schip, Platform::XoChip] {
+            for opcode in [
+                0x9120u16, 0x9121, 0x912F, 0x5120, 0x5122, 0x5124, 0x8128, 0xE19E, 0xE1FF, 0xF1FF,
+            ] {
+                let mut engine = Engine::new(platform, Quirks::new(platform));
+                engine.load_rom(&opcode.to_be_bytes()).unwrap();
+
+                let fault = matches!(
+                    engine.execute_cycle(),
+                    Err(EngineError::OpCodeNotFound { .. })
+                );
+                let data = Instruction::decode(opcode, None, platform) == Instruction::Data(opcode);
+
+                assert_eq!(fault, data, "{:?} {:04X}", platform, opcode);
+            }
+        }
+    }
+
+    #[test]
+    fn quirks() {
+        const PLATFORMS: [Platform; 4] = [
+            Platform::Vip,
+            Platform::Chip48,
+            Platform::Schip,
+            Platform::XoChip,
+        ];
+
+        for platform in PLATFORMS {
+            let quirks = Quirks::new(platform);
+
+            // 8XY6 shifts VX or VY
+            let mut engine = load(platform, &[0x60, 0x01, 0x61, 0x06, 0x80, 0x16]);
+            step(&mut engine, 3);
+            let expected = if quirks.shift { (0, 1) } else { (3, 0) };
+            assert_eq!((engine.get_register(0), engine.get_register(0xF)), expected);
+
+            // BNNN adds V0 or VX
+            let mut engine = load(platform, &[0x60, 0x04, 0x63, 0x08, 0xB3, 0x00]);
+            step(&mut engine, 3);
+            let expected = if quirks.jump { 0x308 } else { 0x304 };
+            assert_eq!(engine.get_pc(), expected, "{:?}", platform);
+
+            // 8XY1 resets VF
+            let mut engine = load(platform, &[0x6F, 0x05, 0x80, 0x11]);
+            step(&mut engine, 2);
+            let expected = if quirks.vf_reset { 0 } else { 5 };
+            assert_eq!(engine.get_register(0xF), expected, "{:?}", platform);
+
+            // DXYN clips or wraps sprites at the right edge
+            let mut engine = load(
+                platform,
+                &[0xA2, 0x08, 0x60, 0x3E, 0xD0, 0x11, 0x12, 0x06, 0xFF],
+            );
+            step(&mut engine, 3);
+            assert_eq!(
+                engine.get_display()[0] == 0,
+                quirks.clipping,
+                "{:?}",
+                platform
+            );
+
+            // FX55 increments I
+            let mut engine = load(platform, &[0xA3, 0x00, 0xF2, 0x55]);
+            step(&mut engine, 2);
+            let expected = if quirks.memory_increment {
+                0x303
+            } else {
+                0x300
+            };
+            assert_eq!(engine.get_index(), expected, "{:?}", platform);
+
+            // DXYN waits for the vertical blank before the next instruction
+            let rom = [0xA2, 0x08, 0xD0, 0x11, 0x60, 0x05, 0x12, 0x06, 0xFF];
+            let mut engine = load(platform, &rom);
+            step(&mut engine, 3);
+            let expected = if quirks.display_wait { 0 } else { 5 };
+            assert_eq!(engine.get_register(0), expected, "{:?}", platform);
+            engine.decrement_timer().unwrap();
+            step(&mut engine, 1);
+            assert_eq!(engine.get_register(0), 5, "{:?}", platform);
+        }
+    }
+
+    #[test]
+    fn schip() {
+        let mut rom = vec![
+            0x00, 0xFF, 0xA2, 0x18, 0x60, 0x00, 0xD0, 0x00, 0x00, 0xFB, 0x00, 0xC2, 0x63, 0x05,
+            0xF3, 0x30, 0xF3, 0x75, 0x63, 0x00, 0xF3, 0x85, 0x00, 0xFD,
+        ];
+        rom.extend([0xFF; 32]);
+        let mut engine = load(Platform::Schip, &rom);
+
+        step(&mut engine, 1);
+        assert_eq!((engine.get_width(), engine.get_height()), (128, 64));
+
+        // DXY0 draws a 16x16 sprite, then it is scrolled right by 4 and down by 2
+        step(&mut engine, 3);
+        assert_eq!(
+            engine.get_display().iter().filter(|p| **p != 0).count(),
+            256
+        );
+        assert_eq!(engine.get_display()[0], 1);
+
+        step(&mut engine, 2);
+        let lit = |x: usize, y: usize| engine.get_display()[x + y * 128] != 0;
+        assert!(!lit(3, 2) && !lit(4, 1) && lit(4, 2) && lit(19, 17) && !lit(20, 17));
+        assert_eq!(
+            engine.get_display().iter().filter(|p| **p != 0).count(),
+            256
+        );
+
+        step(&mut engine, 2);
+        assert_eq!(engine.get_index(), LARGE_FONT_ADDRESS as u16 + 50);
+
+        // The RPL user flags keep V3 while it is overwritten
+        step(&mut engine, 3);
+        assert_eq!(engine.get_register(3), 5);
+
+        step(&mut engine, 2);
+        assert!(engine.is_halted());
+        assert_eq!(engine.get_pc(), 0x216);
+
+        let mut engine = load(Platform::Schip, &[0x00, 0xFF, 0x00, 0xFE]);
+        step(&mut engine, 2);
+        assert_eq!((engine.get_width(), engine.get_height()), (64, 32));
+
+        // Earlier platforms do not know the instructions
+        let mut engine = load(Platform::Chip48, &[0x00, 0xFF]);
+        assert!(matches!(
+            engine.execute_cycle(),
+            Err(EngineError::OpCodeNotFound {
+                op_code: 0x00FF,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn xo_chip() {
+        let mut engine = load(
+            Platform::XoChip,
+            &[
+                0xF0, 0x00, 0xFF, 0x00, 0x60, 0x01, 0x61, 0x02, 0x62, 0x03, 0x50, 0x22, 0x52, 0x03,
+                0xF2, 0x01, 0xA2, 0x20, 0xD3, 0x31, 0xF3, 0x01, 0xD3, 0x31, 0x33, 0x00, 0xF0, 0x00,
+                0x00, 0x00, 0x12, 0x1E, 0xFF, 0x0F,
+            ],
+        );
+
+        // F000 NNNN reaches the whole 64 KiB of memory
+        step(&mut engine, 1);
+        assert_eq!(engine.get_index(), 0xFF00);
+
+        // 5XY2 stores a range of registers and 5XY3 loads it back in reverse order
+        step(&mut engine, 5);
+        assert_eq!(engine.read_memory(0xFF00, 3).unwrap(), [1, 2, 3]);
+        assert_eq!(
+            [0, 1, 2].map(|register| engine.get_register(register)),
+            [3, 2, 1]
+        );
+
+        // Sprites are drawn on the selected planes, one after the other
+        step(&mut engine, 3);
+        assert_eq!(engine.get_planes(), 2);
+        assert_eq!(engine.get_display()[..9], [2, 2, 2, 2, 2, 2, 2, 2, 0]);
+
+        step(&mut engine, 2);
+        assert_eq!(engine.get_display()[..9], [3, 3, 3, 3, 1, 1, 1, 1, 0]);
+
+        // Skips jump over the whole F000 NNNN instruction
+        step(&mut engine, 1);
+        assert_eq!(engine.get_pc(), 0x21E);
+
+        let mut rom = vec![0xA2, 0x08, 0xF0, 0x02, 0x64, 0xC8, 0xF4, 0x3A];
+        rom.extend(0..16);
+        let mut engine = load(Platform::XoChip, &rom);
+        step(&mut engine, 4);
+
+        assert_eq!(
+            engine.get_audio_pattern().to_vec(),
+            (0..16).collect::<Vec<u8>>()
+        );
+        assert_eq!(engine.get_pitch(), 0xC8);
+    }
+
+    #[test]
+    fn faults() {
+        // A subroutine that calls itself fills the 16 levels of the stack
+        let mut engine = load(Platform::Chip48, &[0x22, 0x00]);
+        step(&mut engine, 16);
+        assert!(matches!(
+            engine.execute_cycle(),
+            Err(EngineError::StackOverflow {
+                op_code: 0x2200,
+                pc: 0x200
+            })
+        ));
+
+        let mut engine = load(Platform::Chip48, &[0x00, 0xEE]);
+        assert!(matches!(
+            engine.execute_cycle(),
+            Err(EngineError::StackUnderflow {
+                op_code: 0x00EE,
+                pc: 0x200
+            })
+        ));
+
+        let mut engine = load(Platform::Chip48, &[0xAF, 0xFF, 0xF2, 0x55]);
+        step(&mut engine, 1);
+        assert!(matches!(
+            engine.execute_cycle(),
+            Err(EngineError::MemoryOutOfBounds {
+                address: 0x1001,
+                op_code: 0xF255,
+                pc: 0x202
+            })
+        ));
+
+        let mut engine = load(Platform::Chip48, &[0x1F, 0xFF]);
+        step(&mut engine, 1);
+        assert!(matches!(
+            engine.execute_cycle(),
+            Err(EngineError::PcOutOfBounds { pc: 0xFFF })
+        ));
+
+        assert!(matches!(
+            engine.load_rom(&[0; 0xE01]),
+            Err(EngineError::RomTooLarge { size: 0xE01 })
+        ));
+        assert!(matches!(
+            engine.read_memory(0xFFF, 2),
+            Err(EngineError::InvalidAddress { address: 0x1000 })
+        ));
+        assert!(matches!(
+            engine.set_register(16, 0),
+            Err(EngineError::InvalidRegister { register: 16 })
+        ));
+
+        // XO-CHIP has memory past 0xFFF
+        let mut engine = load(Platform::XoChip, &[0x62, 0xAB, 0xAF, 0xFF, 0xF2, 0x55]);
+        step(&mut engine, 3);
+        assert_eq!(engine.read_memory(0xFFF, 3).unwrap(), [0, 0, 0xAB]);
+    }
+
+    #[test]
+    fn frame_summary() {
+        let mut engine = load(Platform::Chip48, &[0x70, 0x01, 0x12, 0x00]);
+        let summary = engine.run_frame().unwrap();
+        assert_eq!(summary.instructions, DEFAULT_INSTRUCTIONS_PER_FRAME);
+        assert!(!summary.display_changed && !summary.sound_active && !summary.waiting_for_key);
+
+        engine.set_instructions_per_frame(3);
+        assert_eq!(engine.run_frame().unwrap().instructions, 3);
+        assert_eq!(engine.get_frames(), 2);
+
+        // The display and the sound report changes during the frame
+        let mut engine = load(
+            Platform::Chip48,
+            &[
+                0xA2, 0x0A, 0xD0, 0x11, 0x60, 0x02, 0xF0, 0x18, 0x12, 0x08, 0xFF,
+            ],
+        );
+        let summary = engine.run_frame().unwrap();
+        assert!(summary.display_changed && summary.sound_active && summary.sound_toggled);
+
+        let summary = engine.run_frame().unwrap();
+        assert!(!summary.display_changed && !summary.sound_active && summary.sound_toggled);
+
+        // Frames end early when waiting for a key, the vertical blank or after exiting
+        let mut engine = load(Platform::Chip48, &[0x60, 0x01, 0xF0, 0x0A]);
+        let summary = engine.run_frame().unwrap();
+        assert_eq!((summary.instructions, summary.waiting_for_key), (2, true));
+
+        let mut engine = load(Platform::Vip, &[0xA2, 0x06, 0xD0, 0x11, 0x12, 0x02, 0xFF]);
+        assert_eq!(engine.run_frame().unwrap().instructions, 2);
+        assert_eq!(engine.run_frame().unwrap().instructions, 2);
+
+        let mut engine = load(Platform::Schip, &[0x60, 0x01, 0x00, 0xFD]);
+        assert_eq!(engine.run_frame().unwrap().instructions, 2);
+        assert_eq!(engine.run_frame().unwrap().instructions, 0);
+    }
+
+    #[test]
+    fn wait_key() {
+        let rom = [0xF0, 0x0A, 0x12, 0x02];
+
+        // The VIP completes FX0A when the key is released
+        let mut engine = load(Platform::Vip, &rom);
+        engine.key_down(5).unwrap();
+        assert!(engine.run_frame().unwrap().waiting_for_key);
+        engine.key_up(5).unwrap();
+        assert!(!engine.run_frame().unwrap().waiting_for_key);
+        assert_eq!(engine.get_register(0), 5);
+
+        // and SUPER-CHIP when it is pressed
+        let mut engine = load(Platform::Schip, &rom);
+        engine.key_down(5).unwrap();
+        assert!(!engine.run_frame().unwrap().waiting_for_key);
+        assert_eq!(engine.get_register(0), 5);
+
+        // Taps shorter than a frame are not lost
+        let mut engine = load(Platform::Vip, &rom);
+        engine.key_down(9).unwrap();
+        engine.key_up(9).unwrap();
+        engine.run_frame().unwrap();
+        assert_eq!(engine.get_register(0), 9);
+    }
 }
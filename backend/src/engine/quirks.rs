@@ -0,0 +1,149 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::state::errors::StateError;
+use crate::state::{Decoder, Encoder, Snapshot};
+
+use super::constants::{MEMORY_SIZE, XO_CHIP_MEMORY_SIZE};
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Platform {
+    Vip,
+    #[default]
+    Chip48,
+    Schip,
+    XoChip,
+}
+
+impl Platform {
+    pub fn supports_schip(&self) -> bool {
+        matches!(self, Platform::Schip | Platform::XoChip)
+    }
+
+    pub fn supports_xo_chip(&self) -> bool {
+        matches!(self, Platform::XoChip)
+    }
+
+    pub fn memory_size(&self) -> usize {
+        if self.supports_xo_chip() {
+            XO_CHIP_MEMORY_SIZE
+        } else {
+            MEMORY_SIZE
+        }
+    }
+}
+
+impl TryFrom<u8> for Platform {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Platform::Vip),
+            1 => Ok(Platform::Chip48),
+            2 => Ok(Platform::Schip),
+            3 => Ok(Platform::XoChip),
+
+            _ => Err(value),
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quirks {
+    // 8XY6/8XYE shift VX in place and ignore VY
+    pub shift: bool,
+    // FX55/FX65 increment I by X + 1
+    pub memory_increment: bool,
+    // BNNN jumps to XNN + VX instead of NNN + V0
+    pub jump: bool,
+    // 8XY1/8XY2/8XY3 reset VF to 0
+    pub vf_reset: bool,
+    // DXYN clips sprites at the edges of the screen instead of wrapping them
+    pub clipping: bool,
+    // DXYN waits for the vertical blank before continuing
+    pub display_wait: bool,
+    // FX0A waits for the key to be released instead of completing when it is pressed
+    pub key_release: bool,
+}
+
+#[wasm_bindgen]
+impl Quirks {
+    #[wasm_bindgen(constructor)]
+    pub fn new(platform: Platform) -> Self {
+        match platform {
+            Platform::Vip => Self {
+                shift: false,
+                memory_increment: true,
+                jump: false,
+                vf_reset: true,
+                clipping: true,
+                display_wait: true,
+                key_release: true,
+            },
+            Platform::Chip48 => Self {
+                shift: true,
+                memory_increment: false,
+                jump: true,
+                vf_reset: false,
+                clipping: true,
+                display_wait: false,
+                key_release: false,
+            },
+            Platform::Schip => Self {
+                shift: true,
+                memory_increment: false,
+                jump: true,
+                vf_reset: false,
+                clipping: true,
+                display_wait: false,
+                key_release: false,
+            },
+            Platform::XoChip => Self {
+                shift: false,
+                memory_increment: true,
+                jump: false,
+                vf_reset: false,
+                clipping: false,
+                display_wait: false,
+                key_release: true,
+            },
+        }
+    }
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Quirks::new(Platform::default())
+    }
+}
+
+impl From<Platform> for Quirks {
+    fn from(platform: Platform) -> Self {
+        Quirks::new(platform)
+    }
+}
+
+impl Snapshot for Quirks {
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.write_bool(self.shift);
+        encoder.write_bool(self.memory_increment);
+        encoder.write_bool(self.jump);
+        encoder.write_bool(self.vf_reset);
+        encoder.write_bool(self.clipping);
+        encoder.write_bool(self.display_wait);
+        encoder.write_bool(self.key_release);
+    }
+
+    fn load(&mut self, decoder: &mut Decoder) -> Result<(), StateError> {
+        self.shift = decoder.read_bool()?;
+        self.memory_increment = decoder.read_bool()?;
+        self.jump = decoder.read_bool()?;
+        self.vf_reset = decoder.read_bool()?;
+        self.clipping = decoder.read_bool()?;
+        self.display_wait = decoder.read_bool()?;
+        self.key_release = decoder.read_bool()?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,52 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::state::errors::StateError;
+use crate::state::{Decoder, Encoder, Snapshot};
+
 const CYCLE_SIZE: usize = 4096;
 const PHI: u32 = 0x9E3779B9;
+// Interpreters live in the first 512 bytes of memory
+const INTERPRETER_SIZE: u16 = 0x200;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Generator {
+    #[default]
+    MultiplyWithCarry,
+    Xorshift,
+    MemoryWalk,
+}
+
+impl Generator {
+    pub fn create(&self, seed: u32) -> Box<dyn RandomSource> {
+        match self {
+            Generator::MultiplyWithCarry => Box::new(MultiplyWithCarry::new(seed)),
+            Generator::Xorshift => Box::new(Xorshift::new(seed)),
+            Generator::MemoryWalk => Box::new(MemoryWalk::new(seed)),
+        }
+    }
+}
+
+impl TryFrom<u8> for Generator {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Generator::MultiplyWithCarry),
+            1 => Ok(Generator::Xorshift),
+            2 => Ok(Generator::MemoryWalk),
+
+            _ => Err(value),
+        }
+    }
+}
+
+pub trait RandomSource: Snapshot {
+    fn generator(&self) -> Generator;
+
+    // Returns the next number, some generators use the contents of the memory to obtain it
+    fn random(&mut self, memory: &[u8]) -> u32;
+}
 
 pub struct MultiplyWithCarry {
     history: [u32; CYCLE_SIZE],
@@ -26,8 +73,14 @@ impl MultiplyWithCarry {
             index: 4095,
         }
     }
+}
+
+impl RandomSource for MultiplyWithCarry {
+    fn generator(&self) -> Generator {
+        Generator::MultiplyWithCarry
+    }
 
-    pub fn random(&mut self) -> u32 {
+    fn random(&mut self, _memory: &[u8]) -> u32 {
         const A: u64 = 18782;
         const R: u32 = 0xFFFFFFFE;
 
@@ -45,3 +98,203 @@ impl MultiplyWithCarry {
         self.history[self.index]
     }
 }
+
+impl Snapshot for MultiplyWithCarry {
+    fn save(&self, encoder: &mut Encoder) {
+        for value in self.history {
+            encoder.write_u32(value);
+        }
+
+        encoder.write_u32(self.carry);
+        encoder.write_u32(self.index as u32);
+    }
+
+    fn load(&mut self, decoder: &mut Decoder) -> Result<(), StateError> {
+        for value in self.history.iter_mut() {
+            *value = decoder.read_u32()?;
+        }
+
+        self.carry = decoder.read_u32()?;
+        self.index = decoder.read_u32()? as usize & (CYCLE_SIZE - 1);
+
+        Ok(())
+    }
+}
+
+pub struct Xorshift {
+    state: u32,
+}
+
+impl Xorshift {
+    pub fn new(seed: u32) -> Self {
+        // A state of 0 would only produce zeros
+        Self {
+            state: if seed == 0 { PHI } else { seed },
+        }
+    }
+}
+
+impl RandomSource for Xorshift {
+    fn generator(&self) -> Generator {
+        Generator::Xorshift
+    }
+
+    fn random(&mut self, _memory: &[u8]) -> u32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+
+        self.state
+    }
+}
+
+impl Snapshot for Xorshift {
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.write_u32(self.state);
+    }
+
+    fn load(&mut self, decoder: &mut Decoder) -> Result<(), StateError> {
+        self.state = decoder.read_u32()?;
+
+        Ok(())
+    }
+}
+
+// Adds the byte of the interpreter area pointed by an incrementing counter to the previous
+// number, in the spirit of interpreters that drew their numbers from their own code. It does
+// not reproduce the sequence of any of them
+pub struct MemoryWalk {
+    pointer: u16,
+    value: u8,
+}
+
+impl MemoryWalk {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            pointer: seed as u16 % INTERPRETER_SIZE,
+            value: (seed >> 16) as u8,
+        }
+    }
+}
+
+impl RandomSource for MemoryWalk {
+    fn generator(&self) -> Generator {
+        Generator::MemoryWalk
+    }
+
+    fn random(&mut self, memory: &[u8]) -> u32 {
+        self.pointer = (self.pointer + 1) % INTERPRETER_SIZE;
+
+        let byte = memory.get(self.pointer as usize).copied().unwrap_or(0);
+        self.value = self.value.wrapping_add(byte).rotate_left(1) ^ self.pointer as u8;
+
+        self.value as u32
+    }
+}
+
+impl Snapshot for MemoryWalk {
+    fn save(&self, encoder: &mut Encoder) {
+        encoder.write_u16(self.pointer);
+        encoder.write_u8(self.value);
+    }
+
+    fn load(&mut self, decoder: &mut Decoder) -> Result<(), StateError> {
+        self.pointer = decoder.read_u16()? % INTERPRETER_SIZE;
+        self.value = decoder.read_u8()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Generator, RandomSource};
+    use crate::engine::Engine;
+    use crate::engine::quirks::{Platform, Quirks};
+    use crate::state::{Decoder, Encoder};
+
+    const GENERATORS: [Generator; 3] = [
+        Generator::MultiplyWithCarry,
+        Generator::Xorshift,
+        Generator::MemoryWalk,
+    ];
+
+    fn sequence(random: &mut dyn RandomSource, memory: &[u8]) -> Vec<u32> {
+        (0..64).map(|_| random.random(memory)).collect()
+    }
+
+    #[test]
+    fn seeded() {
+        let memory: Vec<u8> = (0..=255).cycle().take(0x200).collect();
+
+        for generator in GENERATORS {
+            let first = sequence(generator.create(1234).as_mut(), &memory);
+
+            assert_eq!(first, sequence(generator.create(1234).as_mut(), &memory));
+            assert_ne!(first, sequence(generator.create(4321).as_mut(), &memory));
+            assert_eq!(Generator::try_from(generator as u8), Ok(generator));
+        }
+
+        // A seed of 0 still produces numbers
+        assert!(
+            sequence(Generator::Xorshift.create(0).as_mut(), &memory)
+                .iter()
+                .any(|n| *n != 0)
+        );
+    }
+
+    #[test]
+    fn snapshot() {
+        let memory = [0x5A; 0x200];
+
+        for generator in GENERATORS {
+            let mut random = generator.create(99);
+            sequence(random.as_mut(), &memory);
+
+            let mut encoder = Encoder::new();
+            random.save(&mut encoder);
+            let data = encoder.into_bytes();
+
+            let mut restored = generator.create(0);
+            let mut decoder = Decoder::new(&data);
+            restored.load(&mut decoder).unwrap();
+            assert!(decoder.is_empty());
+
+            assert_eq!(
+                sequence(restored.as_mut(), &memory),
+                sequence(random.as_mut(), &memory)
+            );
+        }
+    }
+
+    #[test]
+    fn engine_seed() {
+        // Stores random numbers one after another from 0x300
+        let rom = [
+            0xC0, 0xFF, 0xA3, 0x00, 0xF1, 0x1E, 0xF0, 0x55, 0x71, 0x01, 0x12, 0x00,
+        ];
+        let numbers = |engine: &mut Engine| {
+            for _ in 0..16 * 6 {
+                engine.execute_cycle().unwrap();
+            }
+            engine.read_memory(0x300, 16).unwrap().to_vec()
+        };
+
+        for generator in GENERATORS {
+            let mut engine = Engine::new(Platform::Schip, Quirks::new(Platform::Schip));
+            engine.set_generator(generator);
+            engine.set_seed(7);
+            engine.load_rom(&rom).unwrap();
+            let first = numbers(&mut engine);
+
+            // The seed and the generator survive loading the ROM again
+            engine.load_rom(&rom).unwrap();
+            assert_eq!(engine.get_generator(), generator);
+            assert_eq!(numbers(&mut engine), first);
+
+            engine.load_rom(&rom).unwrap();
+            engine.set_seed(8);
+            assert_ne!(numbers(&mut engine), first);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::constants::{VIP_CLEAR_CYCLES, VIP_FETCH_CYCLES};
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Timing {
+    // Every frame executes the same number of instructions
+    #[default]
+    Instructions,
+    // Every frame executes as many instructions as the COSMAC VIP interpreter could
+    Vip,
+}
+
+impl TryFrom<u8> for Timing {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Timing::Instructions),
+            1 => Ok(Timing::Vip),
+
+            _ => Err(value),
+        }
+    }
+}
+
+// Returns the 1802 machine cycles the VIP interpreter spends on an instruction, given the
+// registers before it was executed and whether it skipped the next instruction
+pub fn vip_cycles(opcode: u16, registers: &[u8; 16], skipped: bool) -> u32 {
+    let register_x = ((opcode & 0x0F00) >> 8) as u32;
+    let value_x = registers[register_x as usize] as u32;
+    let rows = (opcode & 0x000F) as u32;
+    let skip = if skipped { 4 } else { 0 };
+
+    let cycles = match opcode & 0xF000 {
+        0x0000 => match opcode {
+            0x00E0 => VIP_CLEAR_CYCLES,
+            0x00EE => 10,
+
+            _ => 12,
+        },
+        0x1000 | 0xA000 => 12,
+        0x2000 => 26,
+        0x3000 | 0x4000 => 10 + skip,
+        0x5000 | 0x9000 | 0xE000 => 14 + skip,
+        0x6000 => 6,
+        0x7000 => 10,
+        0x8000 if rows == 0 => 12,
+        0x8000 => 44,
+        0xB000 => 22,
+        0xC000 => 36,
+        // Rows that are not aligned to a byte are shifted one bit at a time
+        0xD000 => 26 + rows * (36 + 8 * (value_x % 8)),
+        _ => match opcode & 0x00FF {
+            0x1E | 0x29 => 16,
+            // The digits are obtained by repeated subtraction
+            0x33 => 80 + 16 * (value_x / 100 + value_x / 10 % 10 + value_x % 10),
+            0x55 | 0x65 => 14 + 14 * (register_x + 1),
+
+            _ => 10,
+        },
+    };
+
+    VIP_FETCH_CYCLES + cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Timing, vip_cycles};
+    use crate::engine::Engine;
+    use crate::engine::quirks::{Platform, Quirks};
+
+    #[test]
+    fn instruction_cycles() {
+        let mut registers = [0; 16];
+        registers[3] = 3;
+        registers[4] = 255;
+
+        assert_eq!(vip_cycles(0x00E0, &registers, false), 3118);
+        assert_eq!(vip_cycles(0x6000, &registers, false), 46);
+        assert_eq!(vip_cycles(0x3000, &registers, false), 50);
+        assert_eq!(vip_cycles(0x3000, &registers, true), 54);
+        assert_eq!(vip_cycles(0xD035, &registers, false), 246);
+        assert_eq!(vip_cycles(0xD335, &registers, false), 366);
+        assert_eq!(vip_cycles(0xF433, &registers, false), 312);
+        assert_eq!(vip_cycles(0xF255, &registers, false), 96);
+    }
+
+    #[test]
+    fn frame_budget() {
+        let mut engine = Engine::new(Platform::Vip, Quirks::new(Platform::Vip));
+        engine.load_rom(&[0x60, 0x00, 0x12, 0x00]).unwrap();
+        engine.set_timing(Timing::Vip);
+
+        // 1836 cycles per frame, with the last instruction running past the end of the frame
+        assert_eq!(engine.run_frame().unwrap().instructions, 38);
+        assert_eq!(engine.get_machine_cycles(), 1862);
+
+        // which delays the next one
+        assert_eq!(engine.run_frame().unwrap().instructions, 37);
+        assert_eq!(engine.get_machine_cycles(), 3672);
+
+        // The rest of a frame spent waiting for a key is lost
+        engine
+            .load_rom(&[0x60, 0x00, 0xF0, 0x0A, 0x12, 0x02])
+            .unwrap();
+        engine.set_timing(Timing::Vip);
+        assert_eq!(engine.run_frame().unwrap().instructions, 2);
+        engine.key_down(1).unwrap();
+        engine.key_up(1).unwrap();
+        assert_eq!(engine.run_frame().unwrap().instructions, 3);
+    }
+}
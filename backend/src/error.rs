@@ -1,17 +1,100 @@
-use crate::engine::errors::EngineError;
+use std::fmt;
 
-pub trait ErrorTrait {
-    fn to_string(&self) -> String;
-}
+use wasm_bindgen::prelude::wasm_bindgen;
 
+use crate::assembler::errors::AssemblerError;
+use crate::database::errors::DatabaseError;
+use crate::debugger::errors::DebuggerError;
+use crate::engine::errors::EngineError;
+use crate::symbols::errors::SymbolError;
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     EngineError(EngineError),
+    DebuggerError(DebuggerError),
+    AssemblerError(AssemblerError),
+    SymbolError(SymbolError),
+    DatabaseError(DatabaseError),
 }
 
-impl ErrorTrait for Error {
-    fn to_string(&self) -> String {
+impl Error {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::EngineError(e) => e.kind(),
+            Error::DebuggerError(e) => e.kind(),
+            Error::AssemblerError(e) => e.kind(),
+            Error::SymbolError(e) => e.kind(),
+            Error::DatabaseError(e) => e.kind(),
+        }
+    }
+
+    pub fn pc(&self) -> Option<u16> {
         match self {
-            Error::EngineError(e) => e.to_string(),
+            Error::EngineError(e) => e.pc(),
+
+            _ => None,
+        }
+    }
+
+    pub fn op_code(&self) -> Option<u16> {
+        match self {
+            Error::EngineError(e) => e.op_code(),
+
+            _ => None,
+        }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Error::AssemblerError(e) => Some(e.line()),
+            Error::SymbolError(e) => Some(e.line()),
+
+            _ => None,
+        }
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            Error::AssemblerError(e) => Some(e.column()),
+
+            _ => None,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        let mut message = self.to_string();
+        let mut source = std::error::Error::source(self);
+
+        while let Some(e) = source {
+            message = format!("{}: {}", message, e);
+            source = e.source();
+        }
+
+        message
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::EngineError(e) => write!(f, "{}", e),
+            Error::DebuggerError(e) => write!(f, "{}", e),
+            Error::AssemblerError(e) => write!(f, "{}", e),
+            Error::SymbolError(e) => write!(f, "{}", e),
+            Error::DatabaseError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::EngineError(e) => e.source(),
+            Error::DebuggerError(e) => e.source(),
+            Error::AssemblerError(e) => e.source(),
+            Error::SymbolError(e) => e.source(),
+            Error::DatabaseError(e) => e.source(),
         }
     }
 }
@@ -21,3 +104,83 @@ impl From<EngineError> for Error {
         Error::EngineError(err)
     }
 }
+
+impl From<DebuggerError> for Error {
+    fn from(err: DebuggerError) -> Self {
+        Error::DebuggerError(err)
+    }
+}
+
+impl From<AssemblerError> for Error {
+    fn from(err: AssemblerError) -> Self {
+        Error::AssemblerError(err)
+    }
+}
+
+impl From<SymbolError> for Error {
+    fn from(err: SymbolError) -> Self {
+        Error::SymbolError(err)
+    }
+}
+
+impl From<DatabaseError> for Error {
+    fn from(err: DatabaseError) -> Self {
+        Error::DatabaseError(err)
+    }
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug)]
+pub struct Chip8Error {
+    pub kind: String,
+    pub pc: Option<u16>,
+    pub opcode: Option<u16>,
+    pub symbol: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl From<Error> for Chip8Error {
+    fn from(err: Error) -> Self {
+        Self {
+            kind: err.kind().to_string(),
+            pc: err.pc(),
+            opcode: err.op_code(),
+            symbol: None,
+            line: err.line(),
+            column: err.column(),
+            message: err.message(),
+        }
+    }
+}
+
+impl From<EngineError> for Chip8Error {
+    fn from(err: EngineError) -> Self {
+        Chip8Error::from(Error::from(err))
+    }
+}
+
+impl From<DebuggerError> for Chip8Error {
+    fn from(err: DebuggerError) -> Self {
+        Chip8Error::from(Error::from(err))
+    }
+}
+
+impl From<AssemblerError> for Chip8Error {
+    fn from(err: AssemblerError) -> Self {
+        Chip8Error::from(Error::from(err))
+    }
+}
+
+impl From<SymbolError> for Chip8Error {
+    fn from(err: SymbolError) -> Self {
+        Chip8Error::from(Error::from(err))
+    }
+}
+
+impl From<DatabaseError> for Chip8Error {
+    fn from(err: DatabaseError) -> Self {
+        Chip8Error::from(Error::from(err))
+    }
+}
@@ -1 +1,46 @@
 pub const KEY_COUNT: usize = 16;
+pub const EVENT_CAPACITY: usize = 64;
+
+// Host keys in the order of the hexadecimal keypad (0-F), with the block that starts at 1 on
+// the top-left of the keyboard mapped to 1 2 3 C / 4 5 6 D / 7 8 9 E / A 0 B F
+pub const QWERTY_LAYOUT: [&str; KEY_COUNT] = [
+    "x", "1", "2", "3", "q", "w", "e", "a", "s", "d", "z", "c", "4", "r", "f", "v",
+];
+pub const AZERTY_LAYOUT: [&str; KEY_COUNT] = [
+    "x", "1", "2", "3", "a", "z", "e", "q", "s", "d", "w", "c", "4", "r", "f", "v",
+];
+// The top row of AZERTY keyboards produces these characters without shift
+pub const AZERTY_DIGITS: [(&str, u8); 4] = [("&", 0x1), ("é", 0x2), ("\"", 0x3), ("'", 0xC)];
+pub const DVORAK_LAYOUT: [&str; KEY_COUNT] = [
+    "q", "1", "2", "3", "'", ",", ".", "a", "o", "e", ";", "j", "4", "p", "u", "k",
+];
+pub const NUMPAD_LAYOUT: [&str; KEY_COUNT] = [
+    "Numpad0",
+    "Numpad7",
+    "Numpad8",
+    "Numpad9",
+    "Numpad4",
+    "Numpad5",
+    "Numpad6",
+    "Numpad1",
+    "Numpad2",
+    "Numpad3",
+    "NumpadDivide",
+    "NumpadDecimal",
+    "NumpadMultiply",
+    "NumpadSubtract",
+    "NumpadAdd",
+    "NumpadEnter",
+];
+
+// Buttons of the standard gamepad mapping, with the directions on the keys most games use
+pub const GAMEPAD_BINDINGS: [(&str, u8); 8] = [
+    ("GamepadUp", 0x5),
+    ("GamepadDown", 0x8),
+    ("GamepadLeft", 0x7),
+    ("GamepadRight", 0x9),
+    ("GamepadA", 0x6),
+    ("GamepadB", 0x4),
+    ("GamepadX", 0x1),
+    ("GamepadY", 0x2),
+];
@@ -1,15 +1,31 @@
-use crate::error::ErrorTrait;
+use std::fmt;
 
+#[derive(Debug)]
 pub enum InputError {
     OutOfBounds { index: u8, size: usize },
+    InvalidBinding { text: String, line: usize },
 }
 
-impl ErrorTrait for InputError {
-    fn to_string(&self) -> String {
+impl InputError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            InputError::OutOfBounds { .. } => "InputOutOfBounds",
+            InputError::InvalidBinding { .. } => "InputInvalidBinding",
+        }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             InputError::OutOfBounds { index, size } => {
-                format!("Key index out of bounds: {} for size {}", index, size)
+                write!(f, "Key index out of bounds: {} for size {}", index, size)
+            },
+            InputError::InvalidBinding { text, line } => {
+                write!(f, "Invalid key binding {:?} on line {}", text, line)
             },
         }
     }
 }
+
+impl std::error::Error for InputError {}
@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::constants::{
+    AZERTY_DIGITS, AZERTY_LAYOUT, DVORAK_LAYOUT, GAMEPAD_BINDINGS, KEY_COUNT, NUMPAD_LAYOUT,
+    QWERTY_LAYOUT,
+};
+use super::errors::InputError;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    #[default]
+    Qwerty,
+    Azerty,
+    Dvorak,
+    Numpad,
+}
+
+impl Layout {
+    pub fn bindings(&self) -> Vec<(&'static str, u8)> {
+        let keys = match self {
+            Layout::Qwerty => QWERTY_LAYOUT,
+            Layout::Azerty => AZERTY_LAYOUT,
+            Layout::Dvorak => DVORAK_LAYOUT,
+            Layout::Numpad => NUMPAD_LAYOUT,
+        };
+
+        let mut bindings: Vec<(&str, u8)> = (0..KEY_COUNT as u8)
+            .map(|key| (keys[key as usize], key))
+            .collect();
+
+        if *self == Layout::Azerty {
+            bindings.extend(AZERTY_DIGITS);
+        }
+
+        bindings
+    }
+}
+
+// Translates the names of host keys and gamepad buttons into CHIP-8 keys
+pub struct KeyMap {
+    bindings: BTreeMap<String, u8>,
+}
+
+impl KeyMap {
+    pub fn new(layout: Layout) -> Self {
+        let mut map = Self {
+            bindings: BTreeMap::new(),
+        };
+
+        for (host, key) in layout.bindings().into_iter().chain(GAMEPAD_BINDINGS) {
+            map.bindings.insert(normalize(host), key);
+        }
+
+        map
+    }
+
+    // Accepts lines of `name key` pairs, with the key in hexadecimal and `#` starting a comment
+    pub fn parse(text: &str) -> Result<Self, InputError> {
+        let mut map = Self {
+            bindings: BTreeMap::new(),
+        };
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let binding = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [host, key] => u8::from_str_radix(key.trim_start_matches("0x"), 16)
+                    .ok()
+                    .filter(|key| (*key as usize) < KEY_COUNT)
+                    .map(|key| (host, key)),
+
+                _ => None,
+            };
+
+            let (host, key) = binding.ok_or(InputError::InvalidBinding {
+                text: line.to_string(),
+                line: number + 1,
+            })?;
+
+            map.bindings.insert(normalize(host), key);
+        }
+
+        Ok(map)
+    }
+
+    // Adds the bindings of another map, replacing the ones of the same host keys
+    pub fn merge(&mut self, other: KeyMap) {
+        self.bindings.extend(other.bindings);
+    }
+
+    pub fn export(&self) -> String {
+        self.bindings
+            .iter()
+            .map(|(host, key)| format!("{} {:X}\n", host, key))
+            .collect()
+    }
+
+    pub fn bind(&mut self, host: &str, key: u8) -> Result<(), InputError> {
+        if key >= KEY_COUNT as u8 {
+            Err(InputError::OutOfBounds {
+                index: key,
+                size: KEY_COUNT,
+            })?;
+        }
+
+        self.bindings.insert(normalize(host), key);
+
+        Ok(())
+    }
+
+    pub fn unbind(&mut self, host: &str) -> bool {
+        self.bindings.remove(&normalize(host)).is_some()
+    }
+
+    pub fn lookup(&self, host: &str) -> Option<u8> {
+        self.bindings.get(&normalize(host)).copied()
+    }
+}
+
+// Names are case-insensitive so shifted letters map to the same key, and the space character
+// is stored by name so exported bindings stay one pair per line
+fn normalize(host: &str) -> String {
+    match host {
+        " " => "space".to_string(),
+        _ => host.to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyMap, Layout};
+    use crate::input::errors::InputError;
+
+    #[test]
+    fn layouts() {
+        let qwerty = KeyMap::new(Layout::Qwerty);
+        assert_eq!(qwerty.lookup("X"), Some(0x0));
+        assert_eq!(qwerty.lookup("v"), Some(0xF));
+        assert_eq!(qwerty.lookup("GamepadUp"), Some(0x5));
+
+        let azerty = KeyMap::new(Layout::Azerty);
+        assert_eq!(azerty.lookup("a"), Some(0x4));
+        assert_eq!(azerty.lookup("é"), Some(0x2));
+
+        assert_eq!(KeyMap::new(Layout::Dvorak).lookup("'"), Some(0x4));
+        assert_eq!(KeyMap::new(Layout::Numpad).lookup("numpad7"), Some(0x1));
+
+        for layout in [
+            Layout::Qwerty,
+            Layout::Azerty,
+            Layout::Dvorak,
+            Layout::Numpad,
+        ] {
+            let mut keys: Vec<u8> = layout.bindings().iter().map(|(_, key)| *key).collect();
+            keys.sort();
+            keys.dedup();
+            assert_eq!(keys.len(), 16, "{:?}", layout);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut map = KeyMap::new(Layout::Dvorak);
+        map.bind(" ", 0xA).unwrap();
+
+        let text = map.export();
+        assert!(text.contains("space A\n"));
+        assert_eq!(KeyMap::parse(&text).unwrap().export(), text);
+
+        let map = KeyMap::parse("# Comment\n\n  ArrowUp 0x5  \nSPACE a\n").unwrap();
+        assert_eq!(map.lookup("arrowup"), Some(0x5));
+        assert_eq!(map.lookup(" "), Some(0xA));
+        assert_eq!(map.export(), "arrowup 5\nspace A\n");
+    }
+
+    #[test]
+    fn bind() {
+        let mut map = KeyMap::new(Layout::Qwerty);
+
+        map.bind("Q", 0x9).unwrap();
+        assert_eq!(map.lookup("q"), Some(0x9));
+        assert!(matches!(
+            map.bind("q", 0x10),
+            Err(InputError::OutOfBounds { index: 0x10, .. })
+        ));
+
+        assert!(map.unbind("Q"));
+        assert!(!map.unbind("q"));
+        assert_eq!(map.lookup("q"), None);
+
+        // Merged bindings replace the ones of the same keys and keep the others
+        map.merge(KeyMap::parse("x 1\nk 2").unwrap());
+        assert_eq!(map.lookup("x"), Some(0x1));
+        assert_eq!(map.lookup("k"), Some(0x2));
+        assert_eq!(map.lookup("v"), Some(0xF));
+    }
+
+    #[test]
+    fn invalid() {
+        for (text, line) in [("x 1\ny", 2), ("x 10", 1), ("x 1 2", 1), ("\n\nx G", 3)] {
+            assert!(
+                matches!(KeyMap::parse(text), Err(InputError::InvalidBinding { line: found, .. }) if found == line),
+                "{}",
+                text
+            );
+        }
+    }
+}
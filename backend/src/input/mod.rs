@@ -1,21 +1,42 @@
-use constants::KEY_COUNT;
+use std::collections::VecDeque;
+
+use crate::state::errors::StateError;
+use crate::state::{Decoder, Encoder, Snapshot};
+
+use constants::{EVENT_CAPACITY, KEY_COUNT};
 use errors::InputError;
 
-mod constants;
+pub mod constants;
 pub mod errors;
+pub mod mapping;
+
+#[derive(Clone, Copy)]
+struct KeyTransition {
+    key: u8,
+    pressed: bool,
+}
 
 pub struct Input {
     pub keys: [bool; KEY_COUNT],
+    // Transitions not yet consumed by FX0A, in the order they happened
+    events: VecDeque<KeyTransition>,
+    // Number of events queued before the current frame started
+    stale: usize,
+    // Key pressed while FX0A waits for it to be released
+    held: Option<u8>,
 }
 
 impl Input {
     pub fn new() -> Self {
         Self {
             keys: [false; KEY_COUNT],
+            events: VecDeque::new(),
+            stale: 0,
+            held: None,
         }
     }
 
-    pub fn key_down(&mut self, index: u8) -> Result<(), InputError> {
+    pub fn set_key(&mut self, index: u8, pressed: bool) -> Result<(), InputError> {
         if index >= KEY_COUNT as u8 {
             Err(InputError::OutOfBounds {
                 index,
@@ -23,20 +44,22 @@ impl Input {
             })?;
         }
 
-        self.keys[index as usize] = true;
+        // Repeated presses or releases of the same key are not transitions
+        if self.keys[index as usize] == pressed {
+            return Ok(());
+        }
 
-        Ok(())
-    }
+        self.keys[index as usize] = pressed;
 
-    pub fn key_up(&mut self, index: u8) -> Result<(), InputError> {
-        if index >= KEY_COUNT as u8 {
-            Err(InputError::OutOfBounds {
-                index,
-                size: KEY_COUNT,
-            })?;
+        if self.events.len() == EVENT_CAPACITY {
+            self.events.pop_front();
+            self.stale = self.stale.saturating_sub(1);
         }
 
-        self.keys[index as usize] = false;
+        self.events.push_back(KeyTransition {
+            key: index,
+            pressed,
+        });
 
         Ok(())
     }
@@ -51,4 +74,159 @@ impl Input {
 
         Ok(self.keys[index as usize])
     }
+
+    // Transitions are kept until the end of the frame after the one they happened in, so taps
+    // between frames reach FX0A but old ones do not complete it later
+    pub fn next_frame(&mut self) {
+        self.events.drain(..self.stale);
+        self.stale = self.events.len();
+    }
+
+    // Consumes the transitions in order until one completes FX0A, returning its key
+    pub fn wait_key(&mut self, on_release: bool) -> Option<u8> {
+        while let Some(event) = self.events.pop_front() {
+            self.stale = self.stale.saturating_sub(1);
+
+            match (event.pressed, self.held) {
+                (true, None) if !on_release => return Some(event.key),
+                (true, None) => self.held = Some(event.key),
+                (false, Some(key)) if key == event.key => {
+                    self.held = None;
+                    return Some(key);
+                },
+
+                _ => {},
+            }
+        }
+
+        None
+    }
+}
+
+impl Snapshot for Input {
+    fn save(&self, encoder: &mut Encoder) {
+        for key in self.keys {
+            encoder.write_bool(key);
+        }
+
+        encoder.write_u32(self.events.len() as u32);
+
+        for event in &self.events {
+            encoder.write_u8(event.key);
+            encoder.write_bool(event.pressed);
+        }
+
+        encoder.write_u32(self.stale as u32);
+        encoder.write_bool(self.held.is_some());
+        encoder.write_u8(self.held.unwrap_or(0));
+    }
+
+    fn load(&mut self, decoder: &mut Decoder) -> Result<(), StateError> {
+        for key in self.keys.iter_mut() {
+            *key = decoder.read_bool()?;
+        }
+
+        self.events.clear();
+        self.stale = 0;
+        self.held = None;
+
+        for _ in 0..decoder.read_u32()? {
+            self.events.push_back(KeyTransition {
+                key: decoder.read_u8()? % KEY_COUNT as u8,
+                pressed: decoder.read_bool()?,
+            });
+        }
+
+        self.stale = (decoder.read_u32()? as usize).min(self.events.len());
+
+        let held = decoder.read_bool()?;
+        let key = decoder.read_u8()? % KEY_COUNT as u8;
+        self.held = held.then_some(key);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Input;
+    use super::constants::EVENT_CAPACITY;
+    use super::errors::InputError;
+    use crate::state::{Decoder, Encoder, Snapshot};
+
+    #[test]
+    fn wait_key() {
+        let mut input = Input::new();
+
+        // Waiting on release ignores the press of the key
+        input.set_key(3, true).unwrap();
+        assert_eq!(input.wait_key(true), None);
+        input.set_key(7, true).unwrap();
+        input.set_key(7, false).unwrap();
+        assert_eq!(input.wait_key(true), None);
+        input.set_key(3, false).unwrap();
+        assert_eq!(input.wait_key(true), Some(3));
+
+        input.set_key(4, true).unwrap();
+        assert_eq!(input.wait_key(false), Some(4));
+
+        // Repeated presses are not transitions
+        input.set_key(4, true).unwrap();
+        assert_eq!(input.wait_key(false), None);
+
+        assert!(matches!(
+            input.set_key(16, true),
+            Err(InputError::OutOfBounds { index: 16, .. })
+        ));
+    }
+
+    #[test]
+    fn taps() {
+        let mut input = Input::new();
+
+        // A tap between two frames still completes the wait of the next one
+        input.set_key(5, true).unwrap();
+        input.set_key(5, false).unwrap();
+        input.next_frame();
+        assert!(!input.is_key_down(5).unwrap());
+        assert_eq!(input.wait_key(true), Some(5));
+
+        // but not the waits of later frames
+        input.set_key(6, true).unwrap();
+        input.set_key(6, false).unwrap();
+        input.next_frame();
+        input.next_frame();
+        assert_eq!(input.wait_key(true), None);
+
+        // Only the newest transitions are kept
+        for _ in 0..EVENT_CAPACITY {
+            input.set_key(1, true).unwrap();
+            input.set_key(1, false).unwrap();
+        }
+
+        input.set_key(2, true).unwrap();
+        assert_eq!(input.events.len(), EVENT_CAPACITY);
+        assert_eq!(input.events.back().unwrap().key, 2);
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut input = Input::new();
+        input.set_key(9, true).unwrap();
+        input.next_frame();
+        input.set_key(10, true).unwrap();
+        assert_eq!(input.wait_key(true), None);
+
+        let mut encoder = Encoder::new();
+        input.save(&mut encoder);
+        let data = encoder.into_bytes();
+
+        let mut restored = Input::new();
+        restored.load(&mut Decoder::new(&data)).unwrap();
+
+        // The held key of FX0A survives
+        restored.set_key(9, false).unwrap();
+        assert_eq!(restored.wait_key(true), Some(9));
+        assert!(restored.is_key_down(10).unwrap());
+    }
 }
@@ -1,99 +1,945 @@
-use wasm_bindgen::{JsError, prelude::wasm_bindgen};
+use wasm_bindgen::prelude::wasm_bindgen;
 
-use engine::{Engine, HEIGHT, WIDTH};
-use error::ErrorTrait;
+pub use assembler::{Assembly, Label, Monitor};
+pub use audio::Waveform;
+use database::Database;
+pub use database::RomInfo;
+use database::sha1::sha1_hex;
+pub use debugger::{Access, StopKind, StopReason};
+pub use disasm::{DisassembledLine, Syntax};
+pub use display::region::DirtyRegion;
+pub use display::render::Palette;
+use display::render::Renderer;
+use engine::Engine;
+use engine::constants::DEFAULT_INSTRUCTIONS_PER_FRAME;
+use engine::errors::EngineError;
+pub use engine::frame::FrameSummary;
+pub use engine::machine::MachineState;
+pub use engine::quirks::{Platform, Quirks};
+pub use engine::random::Generator;
+pub use engine::timing::Timing;
+pub use error::Chip8Error;
+use input::mapping::KeyMap;
+pub use input::mapping::Layout;
+use rewind::Rewind;
+pub use symbols::SymbolFormat;
+use symbols::SymbolMap;
 
+mod assembler;
+mod audio;
+mod database;
+mod debugger;
+mod disasm;
 mod display;
 mod engine;
 mod error;
 mod input;
-
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = console)]
-    fn log(s: &str);
-}
+mod movie;
+mod rewind;
+mod state;
+mod symbols;
+mod trace;
 
 #[wasm_bindgen]
 pub struct Chip8 {
     engine: Engine,
+    rewind: Rewind,
+    renderer: Renderer,
+    key_map: KeyMap,
+    // Breakpoints declared in the source of the loaded ROM, removed when another one is loaded
+    source_breakpoints: Vec<u32>,
+    // Configuration chosen by the host, restored before a ROM is configured from the database
+    layout: Layout,
+    platform: Platform,
+    quirks: Quirks,
+    instructions_per_frame: u32,
+    database: Database,
+    // Applies the configuration of the database to every ROM that is loaded
+    auto_configure: bool,
+    // Whether the configuration was taken from the database and must be reset for the next ROM
+    rom_configured: bool,
 }
 
 #[wasm_bindgen]
 impl Chip8 {
     #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
+    pub fn new(platform: Option<Platform>) -> Self {
+        let platform = platform.unwrap_or_default();
+
         Self {
-            engine: Engine::new(),
+            engine: Engine::new(platform, Quirks::new(platform)),
+            rewind: Rewind::new(),
+            renderer: Renderer::new(),
+            key_map: KeyMap::new(Layout::default()),
+            source_breakpoints: Vec::new(),
+            layout: Layout::default(),
+            platform,
+            quirks: Quirks::new(platform),
+            instructions_per_frame: DEFAULT_INSTRUCTIONS_PER_FRAME,
+            database: Database::new(),
+            auto_configure: false,
+            rom_configured: false,
         }
     }
 
+    #[wasm_bindgen]
+    pub fn new_with_seed(platform: Option<Platform>, seed: u32) -> Self {
+        let mut chip8 = Self::new(platform);
+        chip8.engine.set_seed(seed);
+
+        chip8
+    }
+
+    #[wasm_bindgen]
+    pub fn get_platform(&self) -> Platform {
+        self.engine.get_platform()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_quirks(&self) -> Quirks {
+        self.engine.get_quirks()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+        self.engine.set_quirks(quirks);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_platform(&mut self, platform: Platform) {
+        self.platform = platform;
+        self.quirks = Quirks::new(platform);
+        self.engine.set_platform(platform);
+    }
+
     pub fn get_width(&self) -> usize {
-        WIDTH
+        self.engine.get_width()
     }
 
     pub fn get_height(&self) -> usize {
-        HEIGHT
+        self.engine.get_height()
     }
 
     #[wasm_bindgen]
-    pub fn load_rom(&mut self, rom_data: &[u8]) -> Result<(), JsError> {
-        if let Err(e) = self.engine.load_rom(rom_data) {
-            return Err(JsError::new(&e.to_string()));
+    pub fn load_rom(&mut self, rom_data: &[u8]) -> Result<(), Chip8Error> {
+        let info = self
+            .database
+            .lookup(&sha1_hex(rom_data))
+            .filter(|_| self.auto_configure)
+            .cloned();
+
+        // The configuration is only changed once the ROM is known to fit in the memory
+        let platform = info.as_ref().and_then(|info| info.platform);
+        Engine::check_rom_size(platform.unwrap_or(self.platform), rom_data.len())?;
+
+        // ROMs that are not in the database run with the configuration of the host
+        match info {
+            Some(info) => self.apply_rom_info(&info),
+            None => self.reset_rom_config(),
+        }
+
+        self.engine.load_rom(rom_data)?;
+        self.rewind.clear();
+
+        for id in self.source_breakpoints.drain(..) {
+            self.engine.debugger().remove(id);
         }
 
         Ok(())
     }
 
+    // SHA-1 of the loaded ROM in lowercase hexadecimal
+    #[wasm_bindgen]
+    pub fn get_rom_hash(&self) -> String {
+        sha1_hex(self.engine.get_rom())
+    }
+
+    #[wasm_bindgen]
+    pub fn lookup_rom(&self) -> Option<RomInfo> {
+        self.lookup_rom_hash(&self.get_rom_hash())
+    }
+
+    #[wasm_bindgen]
+    pub fn lookup_rom_hash(&self, hash: &str) -> Option<RomInfo> {
+        self.database.lookup(hash).cloned()
+    }
+
+    // Adds the programs of a `programs.json` file of the community database
+    #[wasm_bindgen]
+    pub fn load_database(&mut self, json: &str) -> Result<usize, Chip8Error> {
+        Ok(self.database.merge(json)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn get_auto_configure(&self) -> bool {
+        self.auto_configure
+    }
+
+    #[wasm_bindgen]
+    pub fn set_auto_configure(&mut self, auto_configure: bool) {
+        self.auto_configure = auto_configure;
+    }
+
+    // Applies the configuration of the loaded ROM and restarts it, returning whether it is known
+    #[wasm_bindgen]
+    pub fn apply_rom_config(&mut self) -> Result<bool, Chip8Error> {
+        let Some(info) = self.lookup_rom() else {
+            return Ok(false);
+        };
+
+        let platform = info.platform.unwrap_or(self.platform);
+        Engine::check_rom_size(platform, self.engine.get_rom().len())?;
+
+        self.apply_rom_info(&info);
+
+        self.engine.restart()?;
+        self.rewind.clear();
+
+        Ok(true)
+    }
+
+    #[wasm_bindgen]
+    pub fn load_assembly(&mut self, source: &str) -> Result<Assembly, Chip8Error> {
+        let assembly = assembler::assemble(source)?;
+        self.load_rom(&assembly.rom)?;
+        self.engine.set_symbols(assembly.symbols());
+
+        Ok(assembly)
+    }
+
     #[wasm_bindgen]
-    pub fn execute_cycle(&mut self) -> Result<(), JsError> {
-        if let Err(e) = self.engine.execute_cycle() {
-            return Err(JsError::new(&e.to_string()));
+    pub fn load_octo(&mut self, source: &str) -> Result<Assembly, Chip8Error> {
+        let assembly = assembler::octo::compile(source)?;
+        self.load_rom(&assembly.rom)?;
+        self.engine.set_symbols(assembly.symbols());
+
+        for breakpoint in &assembly.breakpoints {
+            let id = self.engine.debugger().add_breakpoint(breakpoint.address);
+            self.source_breakpoints.push(id);
         }
 
+        Ok(assembly)
+    }
+
+    #[wasm_bindgen]
+    pub fn execute_cycle(&mut self) -> Result<(), Chip8Error> {
+        self.engine.execute_cycle().map_err(|e| self.error(e))?;
+
         Ok(())
     }
 
     #[wasm_bindgen]
-    pub fn decrement_timer(&mut self) -> Result<(), JsError> {
-        if let Err(e) = self.engine.decrement_timer() {
-            return Err(JsError::new(&e.to_string()));
-        }
+    pub fn decrement_timer(&mut self) -> Result<(), Chip8Error> {
+        self.engine.decrement_timer()?;
+        self.end_frame();
+
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn run_frame(&mut self) -> Result<FrameSummary, Chip8Error> {
+        let summary = self.engine.run_frame().map_err(|e| self.error(e))?;
+        self.end_frame();
+
+        Ok(summary)
+    }
+
+    #[wasm_bindgen]
+    pub fn get_instructions_per_frame(&self) -> u32 {
+        self.engine.get_instructions_per_frame()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_instructions_per_frame(&mut self, instructions: u32) {
+        self.instructions_per_frame = instructions;
+        self.engine.set_instructions_per_frame(instructions);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_seed(&self) -> u32 {
+        self.engine.get_seed()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_seed(&mut self, seed: u32) {
+        self.engine.set_seed(seed);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_random_generator(&self) -> Generator {
+        self.engine.get_generator()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_random_generator(&mut self, generator: Generator) {
+        self.engine.set_generator(generator);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_timing(&self) -> Timing {
+        self.engine.get_timing()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_timing(&mut self, timing: Timing) {
+        self.engine.set_timing(timing);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_machine_cycles(&self) -> u64 {
+        self.engine.get_machine_cycles()
+    }
+
+    #[wasm_bindgen]
+    pub fn save_state(&self) -> Vec<u8> {
+        self.engine.save_state()
+    }
+
+    #[wasm_bindgen]
+    pub fn load_state(&mut self, state: &[u8]) -> Result<(), Chip8Error> {
+        self.engine.load_state(state)?;
+        self.rewind.clear();
 
         Ok(())
     }
 
+    #[wasm_bindgen]
+    pub fn get_state(&self) -> MachineState {
+        self.engine.get_machine_state()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_register(&self, register: u8) -> u8 {
+        self.engine.get_register(register)
+    }
+
+    #[wasm_bindgen]
+    pub fn set_register(&mut self, register: u8, value: u8) -> Result<(), Chip8Error> {
+        self.engine.set_register(register, value)?;
+
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_index(&self) -> u16 {
+        self.engine.get_index()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_index(&mut self, index: u16) {
+        self.engine.set_index(index);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_pc(&self) -> u16 {
+        self.engine.get_pc()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_pc(&mut self, pc: u16) -> Result<(), Chip8Error> {
+        self.engine.set_pc(pc)?;
+
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_sp(&self) -> u8 {
+        self.engine.get_sp()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_delay_timer(&self) -> u8 {
+        self.engine.get_delay_timer()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_delay_timer(&mut self, value: u8) {
+        self.engine.set_delay_timer(value);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_sound_timer(&self) -> u8 {
+        self.engine.get_sound_timer()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_sound_timer(&mut self, value: u8) {
+        self.engine.set_sound_timer(value);
+    }
+
+    #[wasm_bindgen]
+    pub fn peek_memory(&self, address: u16, length: usize) -> Result<Vec<u8>, Chip8Error> {
+        Ok(self.engine.read_memory(address, length)?.to_vec())
+    }
+
+    #[wasm_bindgen]
+    pub fn poke_memory(&mut self, address: u16, data: &[u8]) -> Result<(), Chip8Error> {
+        self.engine.write_memory(address, data)?;
+
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn disassemble(
+        &self,
+        address: u16,
+        count: usize,
+        syntax: Option<Syntax>,
+    ) -> Vec<DisassembledLine> {
+        self.engine
+            .disassemble(address, count, syntax.unwrap_or_default())
+    }
+
+    #[wasm_bindgen]
+    pub fn run_until_break(&mut self, max_cycles: u32) -> Result<StopReason, Chip8Error> {
+        self.engine
+            .run_until_break(max_cycles)
+            .map_err(|e| self.error(e))
+    }
+
+    #[wasm_bindgen]
+    pub fn add_breakpoint(&mut self, address: u16) -> u32 {
+        self.engine.debugger().add_breakpoint(address)
+    }
+
+    #[wasm_bindgen]
+    pub fn add_conditional_breakpoint(
+        &mut self,
+        condition: &str,
+        address: Option<u16>,
+    ) -> Result<u32, Chip8Error> {
+        Ok(self
+            .engine
+            .debugger()
+            .add_conditional_breakpoint(condition, address)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn add_opcode_breakpoint(&mut self, pattern: &str) -> Result<u32, Chip8Error> {
+        Ok(self.engine.debugger().add_opcode_breakpoint(pattern)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn add_watchpoint(&mut self, address: u16, length: u16, access: Access) -> u32 {
+        self.engine
+            .debugger()
+            .add_watchpoint(address, length, access)
+    }
+
+    #[wasm_bindgen]
+    pub fn remove_breakpoint(&mut self, id: u32) -> bool {
+        self.engine.debugger().remove(id)
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_breakpoints(&mut self) {
+        self.engine.debugger().clear();
+    }
+
+    #[wasm_bindgen]
+    pub fn load_symbols(&mut self, text: &str) -> Result<(), Chip8Error> {
+        self.engine.set_symbols(SymbolMap::parse(text)?);
+
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn export_symbols(&self, format: Option<SymbolFormat>) -> String {
+        self.engine.get_symbols().export(format.unwrap_or_default())
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_symbols(&mut self) {
+        self.engine.set_symbols(SymbolMap::new());
+    }
+
+    #[wasm_bindgen]
+    pub fn get_stack_trace(&self) -> Vec<String> {
+        self.engine.get_stack_trace()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_tracing(&mut self, enabled: bool) {
+        self.engine.tracer().set_enabled(enabled);
+    }
+
+    #[wasm_bindgen]
+    pub fn is_tracing(&self) -> bool {
+        self.engine.get_tracer().is_enabled()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_trace_capacity(&self) -> usize {
+        self.engine.get_tracer().get_capacity()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_trace_capacity(&mut self, capacity: usize) {
+        self.engine.tracer().set_capacity(capacity);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_trace_length(&self) -> usize {
+        self.engine.get_tracer().len()
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_trace(&mut self) {
+        self.engine.tracer().clear();
+    }
+
+    #[wasm_bindgen]
+    pub fn export_trace(&self, syntax: Option<Syntax>) -> String {
+        self.engine
+            .get_tracer()
+            .export_text(syntax.unwrap_or_default())
+    }
+
+    #[wasm_bindgen]
+    pub fn export_trace_binary(&self) -> Vec<u8> {
+        self.engine.get_tracer().export_binary()
+    }
+
+    #[wasm_bindgen]
+    pub fn rewind(&mut self, frames: usize) -> Result<usize, Chip8Error> {
+        Ok(self.rewind.rewind(&mut self.engine, frames)?)
+    }
+
+    #[wasm_bindgen]
+    pub fn step_back(&mut self) -> Result<bool, Chip8Error> {
+        self.rewind
+            .step_back(&mut self.engine)
+            .map_err(|e| self.error(e))
+    }
+
+    #[wasm_bindgen]
+    pub fn get_rewind_frames(&self) -> usize {
+        self.rewind.len()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_rewind_budget(&self) -> usize {
+        self.rewind.get_budget()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_rewind_budget(&mut self, budget: usize) {
+        self.rewind.set_budget(budget);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_cycles(&self) -> u64 {
+        self.engine.get_cycles()
+    }
+
     #[wasm_bindgen]
     pub fn get_display(&self) -> Vec<u8> {
         self.engine.get_display().to_vec()
     }
 
+    // The pixels stay at the same address for the lifetime of the emulator, but views over the
+    // WebAssembly memory are detached whenever it grows
+    #[wasm_bindgen]
+    pub fn get_display_ptr(&self) -> *const u8 {
+        self.engine.get_display().as_ptr()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_display_len(&self) -> usize {
+        self.engine.get_display().len()
+    }
+
+    // Converts the display into RGBA8 pixels using the palette, scale and effects of the renderer
+    #[wasm_bindgen]
+    pub fn render_rgba(&mut self) -> Vec<u8> {
+        self.render_rgba_slice().to_vec()
+    }
+
+    // Same as render_rgba without copying the pixels, which move whenever the output size changes
+    #[wasm_bindgen]
+    pub fn render_rgba_ptr(&mut self) -> *const u8 {
+        self.render_rgba_slice().as_ptr()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_rgba_width(&self) -> usize {
+        self.renderer.get_width()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_rgba_height(&self) -> usize {
+        self.renderer.get_height()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.renderer.set_palette(palette);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_palette_color(&mut self, index: u8, color: u32) {
+        self.renderer.set_color(index, color);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_scale(&self) -> usize {
+        self.renderer.get_scale()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_scale(&mut self, scale: usize) {
+        self.renderer.set_scale(scale);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_scanlines(&self) -> bool {
+        self.renderer.get_scanlines()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_scanlines(&mut self, scanlines: bool) {
+        self.renderer.set_scanlines(scanlines);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_ghosting(&self) -> f32 {
+        self.renderer.get_ghosting()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_ghosting(&mut self, ghosting: f32) {
+        self.renderer.set_ghosting(ghosting);
+    }
+
+    // Whether pixels that turned off are still fading out, so the display has to be rendered
+    // again even if it did not change
+    #[wasm_bindgen]
+    pub fn is_fading(&self) -> bool {
+        self.renderer.is_fading()
+    }
+
+    #[wasm_bindgen]
+    pub fn is_display_dirty(&self) -> bool {
+        self.engine.is_display_dirty()
+    }
+
+    // Returns the area of the display changed since the previous call, if any
+    #[wasm_bindgen]
+    pub fn take_dirty_region(&mut self) -> Option<DirtyRegion> {
+        self.engine.take_dirty_region()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_frame_count(&self) -> u64 {
+        self.engine.get_frames()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_planes(&self) -> u8 {
+        self.engine.get_planes()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_audio_pattern(&self) -> Vec<u8> {
+        self.engine.get_audio_pattern().to_vec()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_pitch(&self) -> u8 {
+        self.engine.get_pitch()
+    }
+
+    #[wasm_bindgen]
+    pub fn is_halted(&self) -> bool {
+        self.engine.is_halted()
+    }
+
     #[wasm_bindgen]
     pub fn is_sound_active(&self) -> bool {
         self.engine.is_sound_active()
     }
 
     #[wasm_bindgen]
-    pub fn key_down(&mut self, key: u8) -> Result<(), JsError> {
-        if let Err(e) = self.engine.key_down(key) {
-            return Err(JsError::new(&e.to_string()));
-        }
+    pub fn fill_audio(&mut self, buffer: &mut [f32], sample_rate: f32) {
+        self.engine.audio().fill(buffer, sample_rate);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_audio_frequency(&self) -> f32 {
+        self.engine.get_audio().get_frequency()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_audio_frequency(&mut self, frequency: f32) {
+        self.engine.audio().set_frequency(frequency);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_audio_volume(&self) -> f32 {
+        self.engine.get_audio().get_volume()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_audio_volume(&mut self, volume: f32) {
+        self.engine.audio().set_volume(volume);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_audio_waveform(&self) -> Waveform {
+        self.engine.get_audio().get_waveform()
+    }
+
+    #[wasm_bindgen]
+    pub fn set_audio_waveform(&mut self, waveform: Waveform) {
+        self.engine.audio().set_waveform(waveform);
+    }
+
+    #[wasm_bindgen]
+    pub fn key_down(&mut self, key: u8) -> Result<(), Chip8Error> {
+        self.engine.key_down(key)?;
 
         Ok(())
     }
 
     #[wasm_bindgen]
-    pub fn key_up(&mut self, key: u8) -> Result<(), JsError> {
-        if let Err(e) = self.engine.key_up(key) {
-            return Err(JsError::new(&e.to_string()));
-        }
+    pub fn key_up(&mut self, key: u8) -> Result<(), Chip8Error> {
+        self.engine.key_up(key)?;
+
+        Ok(())
+    }
+
+    // Takes the name of a host key or gamepad button and returns whether it is bound to a key
+    #[wasm_bindgen]
+    pub fn host_key_down(&mut self, host: &str) -> Result<bool, Chip8Error> {
+        let Some(key) = self.key_map.lookup(host) else {
+            return Ok(false);
+        };
+
+        self.engine.key_down(key)?;
+
+        Ok(true)
+    }
+
+    #[wasm_bindgen]
+    pub fn host_key_up(&mut self, host: &str) -> Result<bool, Chip8Error> {
+        let Some(key) = self.key_map.lookup(host) else {
+            return Ok(false);
+        };
+
+        self.engine.key_up(key)?;
+
+        Ok(true)
+    }
+
+    // Replaces every binding with the ones of the layout and the default gamepad buttons
+    #[wasm_bindgen]
+    pub fn set_keyboard_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.key_map = KeyMap::new(layout);
+    }
+
+    #[wasm_bindgen]
+    pub fn bind_key(&mut self, host: &str, key: u8) -> Result<(), Chip8Error> {
+        self.key_map.bind(host, key).map_err(EngineError::from)?;
+
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn unbind_key(&mut self, host: &str) -> bool {
+        self.key_map.unbind(host)
+    }
+
+    #[wasm_bindgen]
+    pub fn load_key_bindings(&mut self, text: &str) -> Result<(), Chip8Error> {
+        self.key_map = KeyMap::parse(text).map_err(EngineError::from)?;
+
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn export_key_bindings(&self) -> String {
+        self.key_map.export()
+    }
+
+    #[wasm_bindgen]
+    pub fn start_recording(&mut self) -> Result<(), Chip8Error> {
+        self.engine.start_recording()?;
+        self.rewind.clear();
 
         Ok(())
     }
+
+    #[wasm_bindgen]
+    pub fn stop_recording(&mut self) -> Option<Vec<u8>> {
+        self.engine.stop_recording()
+    }
+
+    #[wasm_bindgen]
+    pub fn play_movie(&mut self, movie: &[u8]) -> Result<(), Chip8Error> {
+        self.engine.play_movie(movie)?;
+        self.rewind.clear();
+
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn stop_playback(&mut self) {
+        self.engine.stop_playback();
+    }
+
+    #[wasm_bindgen]
+    pub fn is_recording(&self) -> bool {
+        self.engine.is_recording()
+    }
+
+    #[wasm_bindgen]
+    pub fn is_playing(&self) -> bool {
+        self.engine.is_playing()
+    }
+}
+
+impl Chip8 {
+    fn reset_rom_config(&mut self) {
+        if !std::mem::take(&mut self.rom_configured) {
+            return;
+        }
+
+        self.engine.set_platform(self.platform);
+        self.engine.set_quirks(self.quirks);
+        self.engine
+            .set_instructions_per_frame(self.instructions_per_frame);
+        self.renderer.reset_colors();
+        self.key_map = KeyMap::new(self.layout);
+    }
+
+    // Replaces the configuration left by the previous ROM with the one of the database
+    fn apply_rom_info(&mut self, info: &RomInfo) {
+        self.reset_rom_config();
+        self.rom_configured = true;
+
+        if let Some(platform) = info.platform {
+            self.engine.set_platform(platform);
+        }
+
+        if let Some(quirks) = info.quirks {
+            self.engine.set_quirks(quirks);
+        }
+
+        if let Some(tick_rate) = info.tick_rate {
+            self.engine.set_instructions_per_frame(tick_rate);
+        }
+
+        for (index, color) in info.colors.iter().take(4).enumerate() {
+            self.renderer.set_color(index as u8, *color);
+        }
+
+        if let Ok(bindings) = KeyMap::parse(&info.bindings) {
+            self.key_map.merge(bindings);
+        }
+    }
+
+    fn end_frame(&mut self) {
+        self.rewind.capture(&self.engine);
+        self.renderer.fade(self.engine.get_display());
+    }
+
+    fn render_rgba_slice(&mut self) -> &[u8] {
+        let (width, height) = (self.engine.get_width(), self.engine.get_height());
+
+        self.renderer
+            .render(self.engine.get_display(), width, height)
+    }
+
+    fn error(&self, err: EngineError) -> Chip8Error {
+        let mut error = Chip8Error::from(err);
+
+        if let Some(pc) = error.pc
+            && let Some(symbol) = self.engine.get_symbols().lookup(pc)
+        {
+            error.message = error
+                .message
+                .replace(&format!("at {:#06X}", pc), &format!("at {}", symbol));
+            error.symbol = Some(symbol);
+        }
+
+        error
+    }
+}
+
+#[wasm_bindgen]
+pub fn assemble(source: &str) -> Result<Assembly, Chip8Error> {
+    Ok(assembler::assemble(source)?)
+}
+
+#[wasm_bindgen]
+pub fn compile_octo(source: &str) -> Result<Assembly, Chip8Error> {
+    Ok(assembler::octo::compile(source)?)
 }
 
 impl Default for Chip8 {
     fn default() -> Self {
-        Chip8::new()
+        Chip8::new(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chip8, DEFAULT_INSTRUCTIONS_PER_FRAME, Platform};
+    use crate::database::sha1::sha1_hex;
+
+    fn database(chip8: &mut Chip8, rom: &[u8], platform: &str) {
+        let json = format!(
+            r#"[{{"title": "Test", "roms": {{"{}": {{
+                "platforms": ["{}"], "tickrate": 100, "keys": {{"a": 5}}
+            }}}}}}]"#,
+            sha1_hex(rom),
+            platform
+        );
+
+        chip8.load_database(&json).unwrap();
+    }
+
+    #[test]
+    fn rom_config() {
+        let mut chip8 = Chip8::new(Some(Platform::Chip48));
+        chip8.set_auto_configure(true);
+        database(&mut chip8, &[0x12, 0x00], "xochip");
+
+        chip8.load_rom(&[0x12, 0x00]).unwrap();
+        assert_eq!(chip8.get_platform(), Platform::XoChip);
+        assert_eq!(chip8.get_instructions_per_frame(), 100);
+        assert!(chip8.host_key_down("space").unwrap());
+
+        // The configuration of the host comes back for ROMs that are not in the database
+        chip8.load_rom(&[0x12, 0x02]).unwrap();
+        assert_eq!(chip8.get_platform(), Platform::Chip48);
+        assert_eq!(
+            chip8.get_instructions_per_frame(),
+            DEFAULT_INSTRUCTIONS_PER_FRAME
+        );
+        assert!(!chip8.host_key_down("space").unwrap());
+
+        // Also when the database is not used for the next ROM
+        chip8.load_rom(&[0x12, 0x00]).unwrap();
+        chip8.set_auto_configure(false);
+        chip8.load_rom(&[0x12, 0x00]).unwrap();
+        assert_eq!(chip8.get_platform(), Platform::Chip48);
+    }
+
+    #[test]
+    fn rom_config_too_large() {
+        let rom = vec![0; 0x1000];
+        let mut chip8 = Chip8::new(Some(Platform::XoChip));
+        chip8.set_auto_configure(true);
+        database(&mut chip8, &rom, "chip48");
+
+        assert_eq!(chip8.load_rom(&rom).unwrap_err().kind, "RomTooLarge");
+        assert_eq!(chip8.get_platform(), Platform::XoChip);
+        assert_eq!(
+            chip8.get_instructions_per_frame(),
+            DEFAULT_INSTRUCTIONS_PER_FRAME
+        );
     }
 }
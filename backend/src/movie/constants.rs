@@ -0,0 +1,2 @@
+pub const MAGIC: [u8; 4] = *b"C8MV";
+pub const VERSION: u8 = 3;
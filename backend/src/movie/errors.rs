@@ -0,0 +1,60 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum MovieError {
+    InvalidMagic,
+    UnsupportedVersion { version: u8 },
+    UnknownPlatform { value: u8 },
+    UnknownGenerator { value: u8 },
+    UnknownTiming { value: u8 },
+    InvalidKey { key: u8 },
+    UnexpectedEnd,
+    RomMismatch { expected: u32, found: u32 },
+    Active,
+}
+
+impl MovieError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MovieError::InvalidMagic => "MovieInvalidMagic",
+            MovieError::UnsupportedVersion { .. } => "MovieUnsupportedVersion",
+            MovieError::UnknownPlatform { .. } => "MovieUnknownPlatform",
+            MovieError::UnknownGenerator { .. } => "MovieUnknownGenerator",
+            MovieError::UnknownTiming { .. } => "MovieUnknownTiming",
+            MovieError::InvalidKey { .. } => "MovieInvalidKey",
+            MovieError::UnexpectedEnd => "MovieUnexpectedEnd",
+            MovieError::RomMismatch { .. } => "MovieRomMismatch",
+            MovieError::Active => "MovieActive",
+        }
+    }
+}
+
+impl fmt::Display for MovieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MovieError::InvalidMagic => write!(f, "Data is not a movie"),
+            MovieError::UnsupportedVersion { version } => {
+                write!(f, "Movie version {} is not supported", version)
+            },
+            MovieError::UnknownPlatform { value } => {
+                write!(f, "Unknown platform {} in movie", value)
+            },
+            MovieError::UnknownGenerator { value } => {
+                write!(f, "Unknown random number generator {} in movie", value)
+            },
+            MovieError::UnknownTiming { value } => {
+                write!(f, "Unknown timing {} in movie", value)
+            },
+            MovieError::InvalidKey { key } => write!(f, "Invalid key {} in movie", key),
+            MovieError::UnexpectedEnd => write!(f, "Unexpected end of movie"),
+            MovieError::RomMismatch { expected, found } => write!(
+                f,
+                "Movie was recorded with ROM {:#010X} but ROM {:#010X} is loaded",
+                expected, found
+            ),
+            MovieError::Active => write!(f, "A movie is being recorded or played"),
+        }
+    }
+}
+
+impl std::error::Error for MovieError {}
@@ -0,0 +1,288 @@
+use crate::engine::constants::FLAG_COUNT;
+use crate::engine::quirks::{Platform, Quirks};
+use crate::engine::random::Generator;
+use crate::engine::timing::Timing;
+use crate::input::constants::KEY_COUNT;
+use crate::state::{Decoder, Encoder, Snapshot};
+
+use constants::{MAGIC, VERSION};
+use errors::MovieError;
+
+pub mod constants;
+pub mod errors;
+
+#[derive(Clone, Copy, Debug)]
+pub struct KeyEvent {
+    pub frame: u64,
+    pub cycle: u64,
+    pub key: u8,
+    pub pressed: bool,
+}
+
+impl KeyEvent {
+    // Events are due once the engine has reached the frame and cycle where they were recorded
+    fn is_due(&self, frame: u64, cycle: u64) -> bool {
+        (self.frame, self.cycle) <= (frame, cycle)
+    }
+}
+
+// Everything needed to replay a session: the configuration of the engine when it started and
+// every key transition after it
+pub struct Movie {
+    pub platform: Platform,
+    pub quirks: Quirks,
+    pub generator: Generator,
+    pub seed: u32,
+    pub timing: Timing,
+    pub instructions_per_frame: u32,
+    // RPL user flags survive restarting the ROM, so they are part of the initial state
+    pub flags: [u8; FLAG_COUNT],
+    pub rom_hash: u32,
+    pub events: Vec<KeyEvent>,
+}
+
+impl Movie {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+
+        for byte in MAGIC {
+            encoder.write_u8(byte);
+        }
+
+        encoder.write_u8(VERSION);
+        encoder.write_u8(self.platform as u8);
+        self.quirks.save(&mut encoder);
+        encoder.write_u8(self.generator as u8);
+        encoder.write_u32(self.seed);
+        encoder.write_u8(self.timing as u8);
+        encoder.write_u32(self.instructions_per_frame);
+        encoder.write_bytes(&self.flags);
+        encoder.write_u32(self.rom_hash);
+        encoder.write_u32(self.events.len() as u32);
+
+        for event in &self.events {
+            encoder.write_u64(event.frame);
+            encoder.write_u64(event.cycle);
+            encoder.write_u8(event.key);
+            encoder.write_bool(event.pressed);
+        }
+
+        encoder.into_bytes()
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, MovieError> {
+        let mut decoder = Decoder::new(data);
+        let end = |_| MovieError::UnexpectedEnd;
+
+        if decoder.read_array().map_err(|_| MovieError::InvalidMagic)? != MAGIC {
+            Err(MovieError::InvalidMagic)?;
+        }
+
+        let version = decoder.read_u8().map_err(end)?;
+
+        if version != VERSION {
+            Err(MovieError::UnsupportedVersion { version })?;
+        }
+
+        let value = decoder.read_u8().map_err(end)?;
+        let platform =
+            Platform::try_from(value).map_err(|_| MovieError::UnknownPlatform { value })?;
+
+        let mut quirks = Quirks::new(platform);
+        quirks.load(&mut decoder).map_err(end)?;
+
+        let value = decoder.read_u8().map_err(end)?;
+        let generator =
+            Generator::try_from(value).map_err(|_| MovieError::UnknownGenerator { value })?;
+
+        let seed = decoder.read_u32().map_err(end)?;
+
+        let value = decoder.read_u8().map_err(end)?;
+        let timing = Timing::try_from(value).map_err(|_| MovieError::UnknownTiming { value })?;
+
+        let instructions_per_frame = decoder.read_u32().map_err(end)?;
+        let mut flags = [0; FLAG_COUNT];
+        decoder.read_bytes_into(&mut flags).map_err(end)?;
+
+        let rom_hash = decoder.read_u32().map_err(end)?;
+        let count = decoder.read_u32().map_err(end)?;
+        let mut events = Vec::new();
+
+        for _ in 0..count {
+            let event = KeyEvent {
+                frame: decoder.read_u64().map_err(end)?,
+                cycle: decoder.read_u64().map_err(end)?,
+                key: decoder.read_u8().map_err(end)?,
+                pressed: decoder.read_bool().map_err(end)?,
+            };
+
+            if event.key as usize >= KEY_COUNT {
+                Err(MovieError::InvalidKey { key: event.key })?;
+            }
+
+            events.push(event);
+        }
+
+        Ok(Self {
+            platform,
+            quirks,
+            generator,
+            seed,
+            timing,
+            instructions_per_frame,
+            flags,
+            rom_hash,
+            events,
+        })
+    }
+}
+
+#[derive(Default)]
+pub enum Recorder {
+    #[default]
+    Idle,
+    Recording(Movie),
+    Playing {
+        movie: Movie,
+        position: usize,
+    },
+}
+
+impl Recorder {
+    pub fn is_recording(&self) -> bool {
+        matches!(self, Recorder::Recording(_))
+    }
+
+    pub fn is_playing(&self) -> bool {
+        matches!(self, Recorder::Playing { .. })
+    }
+
+    pub fn record(&mut self, event: KeyEvent) {
+        if let Recorder::Recording(movie) = self {
+            movie.events.push(event);
+        }
+    }
+
+    // Returns the next event of the movie that is due, going back to idle after the last one
+    pub fn next_event(&mut self, frame: u64, cycle: u64) -> Option<KeyEvent> {
+        let Recorder::Playing { movie, position } = self else {
+            return None;
+        };
+
+        let Some(event) = movie.events.get(*position).copied() else {
+            *self = Recorder::Idle;
+            return None;
+        };
+
+        if !event.is_due(frame, cycle) {
+            return None;
+        }
+
+        *position += 1;
+
+        Some(event)
+    }
+
+    pub fn stop(&mut self) -> Option<Movie> {
+        match std::mem::take(self) {
+            Recorder::Recording(movie) | Recorder::Playing { movie, .. } => Some(movie),
+            Recorder::Idle => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyEvent, Movie, errors::MovieError};
+    use crate::engine::constants::FLAG_COUNT;
+    use crate::engine::quirks::{Platform, Quirks};
+    use crate::engine::random::Generator;
+    use crate::engine::timing::Timing;
+
+    fn movie() -> Movie {
+        let mut flags = [0; FLAG_COUNT];
+        flags[3] = 0x7F;
+
+        Movie {
+            platform: Platform::XoChip,
+            quirks: Quirks::new(Platform::Vip),
+            generator: Generator::Xorshift,
+            seed: 0xDEADBEEF,
+            timing: Timing::Vip,
+            instructions_per_frame: 30,
+            flags,
+            rom_hash: 0x12345678,
+            events: vec![
+                KeyEvent {
+                    frame: 1,
+                    cycle: 10,
+                    key: 0x5,
+                    pressed: true,
+                },
+                KeyEvent {
+                    frame: 3,
+                    cycle: 40,
+                    key: 0x5,
+                    pressed: false,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let original = movie();
+        let decoded = Movie::decode(&original.encode()).unwrap();
+
+        assert_eq!(decoded.platform, original.platform);
+        assert_eq!(decoded.quirks, original.quirks);
+        assert_eq!(decoded.generator, original.generator);
+        assert_eq!(decoded.seed, original.seed);
+        assert_eq!(decoded.timing, original.timing);
+        assert_eq!(
+            decoded.instructions_per_frame,
+            original.instructions_per_frame
+        );
+        assert_eq!(decoded.flags, original.flags);
+        assert_eq!(decoded.rom_hash, original.rom_hash);
+
+        let events = |movie: &Movie| -> Vec<_> {
+            movie
+                .events
+                .iter()
+                .map(|event| (event.frame, event.cycle, event.key, event.pressed))
+                .collect()
+        };
+        assert_eq!(events(&decoded), events(&original));
+    }
+
+    #[test]
+    fn invalid() {
+        let data = movie().encode();
+
+        assert!(matches!(
+            Movie::decode(b"C8ST"),
+            Err(MovieError::InvalidMagic)
+        ));
+        assert!(matches!(
+            Movie::decode(&data[..data.len() - 1]),
+            Err(MovieError::UnexpectedEnd)
+        ));
+
+        let mut version = data.clone();
+        version[4] = 0xFF;
+        assert!(matches!(
+            Movie::decode(&version),
+            Err(MovieError::UnsupportedVersion { version: 0xFF })
+        ));
+
+        // The key of the last event is two bytes before its end
+        let mut key = data.clone();
+        let offset = key.len() - 2;
+        key[offset] = 0x10;
+        assert!(matches!(
+            Movie::decode(&key),
+            Err(MovieError::InvalidKey { key: 0x10 })
+        ));
+    }
+}
@@ -0,0 +1,4 @@
+pub const DEFAULT_BUDGET: usize = 4 * 1024 * 1024;
+
+pub const PATCH_GAP: usize = 8;
+pub const PATCH_OVERHEAD: usize = 8;
@@ -0,0 +1,308 @@
+use std::collections::VecDeque;
+
+use crate::engine::Engine;
+use crate::engine::errors::EngineError;
+use crate::movie::errors::MovieError;
+
+use constants::{DEFAULT_BUDGET, PATCH_GAP, PATCH_OVERHEAD};
+
+pub mod constants;
+
+struct Patch {
+    offset: usize,
+    bytes: Vec<u8>,
+}
+
+struct Delta {
+    patches: Vec<Patch>,
+}
+
+impl Delta {
+    fn between(newer: &[u8], older: &[u8]) -> Self {
+        let mut patches: Vec<Patch> = Vec::new();
+        let mut offset = 0;
+
+        while offset < newer.len() {
+            if newer[offset] == older[offset] {
+                offset += 1;
+                continue;
+            }
+
+            let start = offset;
+            let mut end = offset + 1;
+            let mut gap = 0;
+
+            while end < newer.len() && gap < PATCH_GAP {
+                if newer[end] == older[end] {
+                    gap += 1;
+                } else {
+                    gap = 0;
+                }
+
+                end += 1;
+            }
+
+            end -= gap;
+            patches.push(Patch {
+                offset: start,
+                bytes: older[start..end].to_vec(),
+            });
+            offset = end;
+        }
+
+        Self { patches }
+    }
+
+    fn apply(&self, state: &mut [u8]) {
+        for patch in &self.patches {
+            state[patch.offset..patch.offset + patch.bytes.len()].copy_from_slice(&patch.bytes);
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.patches
+            .iter()
+            .map(|patch| patch.bytes.len() + PATCH_OVERHEAD)
+            .sum()
+    }
+}
+
+pub struct Rewind {
+    head: Option<Vec<u8>>,
+    deltas: VecDeque<Delta>,
+    budget: usize,
+    size: usize,
+}
+
+impl Rewind {
+    pub fn new() -> Self {
+        Self {
+            head: None,
+            deltas: VecDeque::new(),
+            budget: DEFAULT_BUDGET,
+            size: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.budget > 0
+    }
+
+    pub fn get_budget(&self) -> usize {
+        self.budget
+    }
+
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.trim();
+    }
+
+    pub fn len(&self) -> usize {
+        self.deltas.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.head = None;
+        self.deltas.clear();
+        self.size = 0;
+    }
+
+    pub fn capture(&mut self, engine: &Engine) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let state = engine.save_state();
+
+        match self.head.take() {
+            Some(head) if head.len() == state.len() => {
+                let delta = Delta::between(&state, &head);
+
+                self.size += delta.size();
+                self.deltas.push_back(delta);
+            },
+            _ => self.clear(),
+        }
+
+        self.head = Some(state);
+        self.trim();
+    }
+
+    fn pop(&mut self, frames: usize) -> usize {
+        let Some(head) = self.head.as_mut() else {
+            return 0;
+        };
+
+        let frames = frames.min(self.deltas.len());
+
+        for delta in self.deltas.drain(self.deltas.len() - frames..).rev() {
+            delta.apply(head);
+            self.size -= delta.size();
+        }
+
+        frames
+    }
+
+    pub fn rewind(&mut self, engine: &mut Engine, frames: usize) -> Result<usize, EngineError> {
+        check_movie(engine)?;
+
+        let frames = self.pop(frames);
+
+        if let Some(head) = &self.head {
+            engine.load_state(head)?;
+        }
+
+        Ok(frames)
+    }
+
+    pub fn step_back(&mut self, engine: &mut Engine) -> Result<bool, EngineError> {
+        check_movie(engine)?;
+
+        let Some(target) = engine.get_cycles().checked_sub(1) else {
+            return Ok(false);
+        };
+
+        let current = engine.save_state();
+
+        loop {
+            let Some(head) = &self.head else {
+                return Ok(false);
+            };
+
+            engine.load_state(head)?;
+
+            if engine.get_cycles() <= target {
+                break;
+            }
+
+            if self.pop(1) == 0 {
+                engine.load_state(&current)?;
+
+                return Ok(false);
+            }
+        }
+
+        engine.replay(target)?;
+
+        Ok(true)
+    }
+
+    fn trim(&mut self) {
+        let head = self.head.as_ref().map_or(0, |head| head.len());
+
+        while head + self.size > self.budget {
+            match self.deltas.pop_front() {
+                Some(delta) => self.size -= delta.size(),
+                None => break,
+            }
+        }
+
+        if head > self.budget {
+            self.clear();
+        }
+    }
+}
+
+// Restoring a state would desynchronize the movie from the machine, so both are refused while
+// a movie is being recorded or played
+fn check_movie(engine: &Engine) -> Result<(), EngineError> {
+    if engine.is_recording() || engine.is_playing() {
+        Err(MovieError::Active)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rewind;
+    use crate::debugger::Access;
+    use crate::engine::Engine;
+    use crate::engine::quirks::{Platform, Quirks};
+
+    // Draws the sprite at 0x208 and increments V0 forever
+    const ROM: [u8; 13] = [
+        0xA2, 0x08, 0xD0, 0x15, 0x70, 0x01, 0x12, 0x02, 0xF0, 0x90, 0xF0, 0x90, 0xF0,
+    ];
+
+    fn engine() -> (Engine, Rewind) {
+        let mut engine = Engine::new(Platform::Chip48, Quirks::new(Platform::Chip48));
+        engine.load_rom(&ROM).unwrap();
+
+        let mut rewind = Rewind::new();
+        rewind.capture(&engine);
+
+        for _ in 0..10 {
+            engine.run_frame().unwrap();
+            rewind.capture(&engine);
+        }
+
+        (engine, rewind)
+    }
+
+    #[test]
+    fn rewind() {
+        let (mut engine, mut rewind) = engine();
+        let cycles = engine.get_cycles();
+        let state = engine.save_state();
+
+        assert_eq!(rewind.len(), 10);
+        assert_eq!(rewind.rewind(&mut engine, 3).unwrap(), 3);
+        assert_eq!(rewind.len(), 7);
+        assert!(engine.get_cycles() < cycles);
+
+        // Running the same frames again reaches the same state
+        for _ in 0..3 {
+            engine.run_frame().unwrap();
+        }
+
+        assert_eq!(engine.save_state(), state);
+        assert_eq!(rewind.rewind(&mut engine, 100).unwrap(), 7);
+        assert_eq!(engine.get_cycles(), 0);
+    }
+
+    #[test]
+    fn step_back() {
+        let (mut engine, mut rewind) = engine();
+        let cycles = engine.get_cycles();
+        engine.execute_cycle().unwrap();
+        let state = engine.save_state();
+        engine.execute_cycle().unwrap();
+
+        assert!(rewind.step_back(&mut engine).unwrap());
+        assert_eq!(engine.get_cycles(), cycles + 1);
+        assert_eq!(engine.save_state(), state);
+    }
+
+    #[test]
+    fn step_back_replay() {
+        let (mut engine, mut rewind) = engine();
+        engine.tracer().set_enabled(true);
+        engine.debugger().add_watchpoint(0x208, 5, Access::Read);
+        engine.execute_cycle().unwrap();
+        let entries = engine.get_tracer().len();
+
+        // The instructions executed again are neither traced nor reported to the debugger
+        assert!(rewind.step_back(&mut engine).unwrap());
+        assert_eq!(engine.get_tracer().len(), entries);
+        assert!(engine.tracer().is_enabled());
+        assert!(engine.debugger().take_hit().is_none());
+    }
+
+    #[test]
+    fn movie() {
+        let (mut engine, mut rewind) = engine();
+        engine.start_recording().unwrap();
+        engine.run_frame().unwrap();
+
+        assert_eq!(
+            rewind.rewind(&mut engine, 1).unwrap_err().kind(),
+            "MovieActive"
+        );
+        assert_eq!(
+            rewind.step_back(&mut engine).unwrap_err().kind(),
+            "MovieActive"
+        );
+        assert!(engine.is_recording());
+    }
+}
@@ -0,0 +1,17 @@
+pub const MAGIC: [u8; 4] = *b"C8ST";
+pub const MAJOR_VERSION: u8 = 1;
+// Every field added to a section bumps the minor version. States before 1.3 grew fields without
+// a bump, so their layout cannot be told apart and they are rejected
+pub const MINOR_VERSION: u8 = 3;
+pub const OLDEST_MINOR_VERSION: u8 = 3;
+
+pub const HEADER_SIZE: usize = 7;
+pub const CHECKSUM_SIZE: usize = 4;
+
+pub const CPU_SECTION: [u8; 4] = *b"CPU ";
+pub const MEMORY_SECTION: [u8; 4] = *b"MEM ";
+pub const QUIRKS_SECTION: [u8; 4] = *b"QRKS";
+pub const DISPLAY_SECTION: [u8; 4] = *b"DISP";
+pub const INPUT_SECTION: [u8; 4] = *b"INPT";
+pub const RANDOM_SECTION: [u8; 4] = *b"RAND";
+pub const SEED_SECTION: [u8; 4] = *b"SEED";
@@ -0,0 +1,71 @@
+use std::fmt;
+
+use crate::engine::quirks::Platform;
+
+#[derive(Debug)]
+pub enum StateError {
+    InvalidMagic,
+    UnsupportedVersion { major: u8, minor: u8 },
+    ChecksumMismatch { expected: u32, found: u32 },
+    PlatformMismatch { expected: Platform, found: Platform },
+    UnknownPlatform { value: u8 },
+    MissingSection { tag: [u8; 4] },
+    InvalidSection { tag: [u8; 4] },
+    UnexpectedEnd,
+}
+
+impl StateError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            StateError::InvalidMagic => "StateInvalidMagic",
+            StateError::UnsupportedVersion { .. } => "StateUnsupportedVersion",
+            StateError::ChecksumMismatch { .. } => "StateChecksumMismatch",
+            StateError::PlatformMismatch { .. } => "StatePlatformMismatch",
+            StateError::UnknownPlatform { .. } => "StateUnknownPlatform",
+            StateError::MissingSection { .. } => "StateMissingSection",
+            StateError::InvalidSection { .. } => "StateInvalidSection",
+            StateError::UnexpectedEnd => "StateUnexpectedEnd",
+        }
+    }
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::InvalidMagic => write!(f, "Data is not a save state"),
+            StateError::UnsupportedVersion { major, minor } => {
+                write!(f, "Save state version {}.{} is not supported", major, minor)
+            },
+            StateError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "Save state checksum mismatch: expected {:#010X}, found {:#010X}",
+                expected, found
+            ),
+            StateError::PlatformMismatch { expected, found } => write!(
+                f,
+                "Save state was produced for platform {:?} but the engine runs platform {:?}",
+                found, expected
+            ),
+            StateError::UnknownPlatform { value } => {
+                write!(f, "Save state platform {} is unknown", value)
+            },
+            StateError::MissingSection { tag } => {
+                write!(
+                    f,
+                    "Save state section {:?} is missing",
+                    String::from_utf8_lossy(tag)
+                )
+            },
+            StateError::InvalidSection { tag } => {
+                write!(
+                    f,
+                    "Save state section {:?} is invalid",
+                    String::from_utf8_lossy(tag)
+                )
+            },
+            StateError::UnexpectedEnd => write!(f, "Save state ended unexpectedly"),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
@@ -0,0 +1,315 @@
+use crate::engine::quirks::Platform;
+
+use constants::{
+    CHECKSUM_SIZE, HEADER_SIZE, MAGIC, MAJOR_VERSION, MINOR_VERSION, OLDEST_MINOR_VERSION,
+};
+use crc::crc32;
+use errors::StateError;
+
+pub mod constants;
+pub mod crc;
+pub mod errors;
+
+pub trait Snapshot {
+    fn save(&self, encoder: &mut Encoder);
+    fn load(&mut self, decoder: &mut Decoder) -> Result<(), StateError>;
+}
+
+pub struct Encoder {
+    buffer: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self { buffer: Vec::new() }
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_u32(bytes.len() as u32);
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+pub struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], StateError> {
+        let end = self.position + length;
+
+        if end > self.data.len() {
+            Err(StateError::UnexpectedEnd)?;
+        }
+
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, StateError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, StateError> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, StateError> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, StateError> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, StateError> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], StateError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+
+        Ok(array)
+    }
+
+    pub fn read_bytes(&mut self) -> Result<&'a [u8], StateError> {
+        let length = self.read_u32()? as usize;
+
+        self.take(length)
+    }
+
+    pub fn read_bytes_into(&mut self, buffer: &mut [u8]) -> Result<(), StateError> {
+        let bytes = self.read_bytes()?;
+
+        if bytes.len() != buffer.len() {
+            Err(StateError::UnexpectedEnd)?;
+        }
+
+        buffer.copy_from_slice(bytes);
+
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position >= self.data.len()
+    }
+}
+
+pub struct Sections<'a> {
+    sections: Vec<([u8; 4], &'a [u8])>,
+}
+
+impl<'a> Sections<'a> {
+    pub fn get(&self, tag: [u8; 4]) -> Result<Decoder<'a>, StateError> {
+        self.sections
+            .iter()
+            .find(|(section, _)| *section == tag)
+            .map(|(_, data)| Decoder::new(data))
+            .ok_or(StateError::MissingSection { tag })
+    }
+
+    pub fn load(
+        &self,
+        tag: [u8; 4],
+        target: &mut (impl Snapshot + ?Sized),
+    ) -> Result<(), StateError> {
+        let mut decoder = self.get(tag)?;
+
+        match target.load(&mut decoder) {
+            Ok(()) if decoder.is_empty() => Ok(()),
+            _ => Err(StateError::InvalidSection { tag }),
+        }
+    }
+}
+
+pub fn write_state(platform: Platform, sections: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let mut data = Vec::new();
+
+    data.extend_from_slice(&MAGIC);
+    data.push(MAJOR_VERSION);
+    data.push(MINOR_VERSION);
+    data.push(platform as u8);
+
+    for (tag, section) in sections {
+        data.extend_from_slice(tag);
+        data.extend_from_slice(&(section.len() as u32).to_le_bytes());
+        data.extend_from_slice(section);
+    }
+
+    let checksum = crc32(&data);
+    data.extend_from_slice(&checksum.to_le_bytes());
+
+    data
+}
+
+pub fn read_state(data: &[u8], platform: Platform) -> Result<Sections<'_>, StateError> {
+    if data.len() < HEADER_SIZE + CHECKSUM_SIZE || data[0..4] != MAGIC {
+        Err(StateError::InvalidMagic)?;
+    }
+
+    let (body, checksum) = data.split_at(data.len() - CHECKSUM_SIZE);
+    let expected = u32::from_le_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+    let found = crc32(body);
+
+    if expected != found {
+        Err(StateError::ChecksumMismatch { expected, found })?;
+    }
+
+    let mut decoder = Decoder::new(&body[MAGIC.len()..]);
+
+    let major = decoder.read_u8()?;
+    let minor = decoder.read_u8()?;
+
+    if major != MAJOR_VERSION || !(OLDEST_MINOR_VERSION..=MINOR_VERSION).contains(&minor) {
+        Err(StateError::UnsupportedVersion { major, minor })?;
+    }
+
+    let value = decoder.read_u8()?;
+    let found = Platform::try_from(value).map_err(|_| StateError::UnknownPlatform { value })?;
+
+    if found != platform {
+        Err(StateError::PlatformMismatch {
+            expected: platform,
+            found,
+        })?;
+    }
+
+    let mut sections = Vec::new();
+
+    while !decoder.is_empty() {
+        let tag = decoder.read_array()?;
+        let length = decoder.read_u32()? as usize;
+
+        sections.push((tag, decoder.take(length)?));
+    }
+
+    Ok(Sections { sections })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::crc::crc32;
+    use super::errors::StateError;
+    use super::{Decoder, Encoder, read_state, write_state};
+    use crate::engine::quirks::{Platform, Quirks};
+
+    #[test]
+    fn encoder_round_trip() {
+        let mut encoder = Encoder::new();
+        encoder.write_u8(0xAB);
+        encoder.write_bool(true);
+        encoder.write_u16(0x1234);
+        encoder.write_u32(0xDEADBEEF);
+        encoder.write_u64(u64::MAX - 1);
+        encoder.write_bytes(&[1, 2, 3]);
+
+        let data = encoder.into_bytes();
+        let mut decoder = Decoder::new(&data);
+
+        assert_eq!(decoder.read_u8().unwrap(), 0xAB);
+        assert!(decoder.read_bool().unwrap());
+        assert_eq!(decoder.read_u16().unwrap(), 0x1234);
+        assert_eq!(decoder.read_u32().unwrap(), 0xDEADBEEF);
+        assert_eq!(decoder.read_u64().unwrap(), u64::MAX - 1);
+        assert_eq!(decoder.read_bytes().unwrap(), [1, 2, 3]);
+        assert!(decoder.is_empty());
+        assert!(matches!(decoder.read_u8(), Err(StateError::UnexpectedEnd)));
+    }
+
+    #[test]
+    fn sections() {
+        let data = write_state(
+            Platform::Schip,
+            &[(*b"AAAA", vec![1, 2]), (*b"BBBB", vec![])],
+        );
+        let sections = read_state(&data, Platform::Schip).unwrap();
+
+        assert_eq!(sections.get(*b"AAAA").unwrap().read_u16().unwrap(), 0x0201);
+        assert!(sections.get(*b"BBBB").unwrap().is_empty());
+        assert!(matches!(
+            sections.get(*b"CCCC"),
+            Err(StateError::MissingSection { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid() {
+        let data = write_state(Platform::Vip, &[(*b"AAAA", vec![1, 2, 3])]);
+
+        assert!(matches!(
+            read_state(&data, Platform::XoChip),
+            Err(StateError::PlatformMismatch { .. })
+        ));
+        assert!(matches!(
+            read_state(&data[1..], Platform::Vip),
+            Err(StateError::InvalidMagic)
+        ));
+
+        let mut corrupted = data.clone();
+        corrupted[12] ^= 0xFF;
+        assert!(matches!(
+            read_state(&corrupted, Platform::Vip),
+            Err(StateError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn versions() {
+        let data = write_state(Platform::Vip, &[]);
+
+        for minor in [0, 2, 4] {
+            let mut data = data[..data.len() - 4].to_vec();
+            data[5] = minor;
+            data.extend_from_slice(&crc32(&data).to_le_bytes());
+
+            assert!(matches!(
+                read_state(&data, Platform::Vip),
+                Err(StateError::UnsupportedVersion { major: 1, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn section_length() {
+        // Sections must contain exactly the fields of the version, no fewer and no more
+        for (length, valid) in [(6, false), (7, true), (8, false)] {
+            let data = write_state(Platform::Vip, &[(*b"QRKS", vec![1; length])]);
+            let sections = read_state(&data, Platform::Vip).unwrap();
+            let result = sections.load(*b"QRKS", &mut Quirks::new(Platform::Vip));
+
+            assert_eq!(result.is_ok(), valid, "{}", length);
+        }
+    }
+}
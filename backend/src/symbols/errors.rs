@@ -0,0 +1,32 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum SymbolError {
+    InvalidSymbol { text: String, line: usize },
+}
+
+impl SymbolError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SymbolError::InvalidSymbol { .. } => "InvalidSymbol",
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        match self {
+            SymbolError::InvalidSymbol { line, .. } => *line,
+        }
+    }
+}
+
+impl fmt::Display for SymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolError::InvalidSymbol { text, line } => {
+                write!(f, "{}: Invalid symbol {:?}", line, text)
+            },
+        }
+    }
+}
+
+impl std::error::Error for SymbolError {}
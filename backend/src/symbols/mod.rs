@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::debugger::condition::parse_number;
+
+use errors::SymbolError;
+
+pub mod errors;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SymbolFormat {
+    #[default]
+    Octo,
+    Simple,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SymbolMap {
+    labels: BTreeMap<u16, String>,
+}
+
+impl SymbolMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Accepts Octo declarations (`:const main 0x202`) and `addr label` pairs in either order
+    pub fn parse(text: &str) -> Result<Self, SymbolError> {
+        let mut symbols = Self::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split(['#', ';']).next().unwrap_or_default();
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+
+            let symbol = match tokens[..] {
+                [] => continue,
+                [":breakpoint" | ":monitor", ..] => continue,
+                [":const" | ":" | ":label", name, address] => {
+                    parse_number(address).map(|address| (address, name))
+                },
+                [first, second] => match parse_number(first) {
+                    Some(address) => Some((address, second)),
+                    None => parse_number(second).map(|address| (address, first)),
+                },
+
+                _ => None,
+            };
+
+            let (address, name) = symbol.ok_or(SymbolError::InvalidSymbol {
+                text: line.trim().to_string(),
+                line: number + 1,
+            })?;
+
+            symbols.insert(address, name);
+        }
+
+        Ok(symbols)
+    }
+
+    pub fn insert(&mut self, address: u16, name: &str) {
+        self.labels
+            .entry(address)
+            .or_insert_with(|| name.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn lookup(&self, address: u16) -> Option<String> {
+        let (label, name) = self.labels.range(..=address).next_back()?;
+
+        Some(match address - label {
+            0 => name.clone(),
+            offset => format!("{}+{:#04X}", name, offset),
+        })
+    }
+
+    pub fn format_address(&self, address: u16) -> String {
+        self.lookup(address)
+            .unwrap_or_else(|| format!("{:#06X}", address))
+    }
+
+    pub fn export(&self, format: SymbolFormat) -> String {
+        let mut text = String::new();
+
+        for (address, name) in &self.labels {
+            let line = match format {
+                SymbolFormat::Octo => format!(":const {} {:#06X}\n", name, address),
+                SymbolFormat::Simple => format!("{:#06X} {}\n", address, name),
+            };
+
+            text.push_str(&line);
+        }
+
+        text
+    }
+}
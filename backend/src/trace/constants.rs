@@ -0,0 +1,8 @@
+pub const MAGIC: [u8; 4] = *b"C8TR";
+pub const VERSION: u8 = 1;
+
+pub const DEFAULT_CAPACITY: usize = 4096;
+
+pub const INDEX_TARGET: u8 = 0x10;
+pub const DELAY_TIMER_TARGET: u8 = 0x11;
+pub const SOUND_TIMER_TARGET: u8 = 0x12;
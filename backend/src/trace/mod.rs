@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+
+use crate::disasm::instruction::Instruction;
+use crate::disasm::{self, Syntax};
+use crate::state::Encoder;
+
+use constants::{
+    DEFAULT_CAPACITY, DELAY_TIMER_TARGET, INDEX_TARGET, MAGIC, SOUND_TIMER_TARGET, VERSION,
+};
+
+pub mod constants;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CpuState {
+    pub registers: [u8; 16],
+    pub index: u16,
+    pub delay_timer: u8,
+    pub sound_timer: u8,
+}
+
+#[derive(Clone, Copy)]
+enum Change {
+    Register(u8, u8),
+    Index(u16),
+    DelayTimer(u8),
+    SoundTimer(u8),
+}
+
+impl Change {
+    fn between(before: &CpuState, after: &CpuState) -> Vec<Change> {
+        let mut changes: Vec<Change> = (0..16)
+            .filter(|&register| before.registers[register] != after.registers[register])
+            .map(|register| Change::Register(register as u8, after.registers[register]))
+            .collect();
+
+        if before.index != after.index {
+            changes.push(Change::Index(after.index));
+        }
+
+        if before.delay_timer != after.delay_timer {
+            changes.push(Change::DelayTimer(after.delay_timer));
+        }
+
+        if before.sound_timer != after.sound_timer {
+            changes.push(Change::SoundTimer(after.sound_timer));
+        }
+
+        changes
+    }
+
+    fn encode(&self) -> (u8, u16) {
+        match *self {
+            Change::Register(register, value) => (register, value as u16),
+            Change::Index(value) => (INDEX_TARGET, value),
+            Change::DelayTimer(value) => (DELAY_TIMER_TARGET, value as u16),
+            Change::SoundTimer(value) => (SOUND_TIMER_TARGET, value as u16),
+        }
+    }
+
+    fn text(&self) -> String {
+        match *self {
+            Change::Register(register, value) => format!("V{:X}={:02X}", register, value),
+            Change::Index(value) => format!("I={:04X}", value),
+            Change::DelayTimer(value) => format!("DT={:02X}", value),
+            Change::SoundTimer(value) => format!("ST={:02X}", value),
+        }
+    }
+}
+
+struct Entry {
+    cycle: u64,
+    pc: u16,
+    instruction: Instruction,
+    changes: Vec<Change>,
+}
+
+pub struct Tracer {
+    entries: VecDeque<Entry>,
+    capacity: usize,
+    enabled: bool,
+}
+
+impl Tracer {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: DEFAULT_CAPACITY,
+            enabled: false,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn record(
+        &mut self,
+        cycle: u64,
+        pc: u16,
+        instruction: Instruction,
+        before: &CpuState,
+        after: &CpuState,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(Entry {
+            cycle,
+            pc,
+            instruction,
+            changes: Change::between(before, after),
+        });
+    }
+
+    // One line per instruction: cycle, PC, opcode, mnemonic and the values that changed
+    pub fn export_text(&self, syntax: Syntax) -> String {
+        let mut text = String::new();
+
+        for entry in &self.entries {
+            let opcode: String = entry
+                .instruction
+                .encode()
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect();
+
+            let mut line = format!(
+                "{:>8} {:04X} {:<8} {:<24}",
+                entry.cycle,
+                entry.pc,
+                opcode,
+                disasm::format(&entry.instruction, syntax)
+            );
+
+            for change in &entry.changes {
+                line.push(' ');
+                line.push_str(&change.text());
+            }
+
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+
+        text
+    }
+
+    pub fn export_binary(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+
+        for byte in MAGIC {
+            encoder.write_u8(byte);
+        }
+
+        encoder.write_u8(VERSION);
+        encoder.write_u32(self.entries.len() as u32);
+
+        for entry in &self.entries {
+            let bytes = entry.instruction.encode();
+
+            encoder.write_u64(entry.cycle);
+            encoder.write_u16(entry.pc);
+            encoder.write_u8(bytes.len() as u8);
+
+            for byte in bytes {
+                encoder.write_u8(byte);
+            }
+
+            encoder.write_u8(entry.changes.len() as u8);
+
+            for change in &entry.changes {
+                let (target, value) = change.encode();
+
+                encoder.write_u8(target);
+                encoder.write_u16(value);
+            }
+        }
+
+        encoder.into_bytes()
+    }
+}
@@ -2,41 +2,25 @@
 
 import { useEffect, useRef, useState } from "react";
 
-import init, { Chip8 } from "@/utils/wasm/chip_8";
-
-const KEY_MAP: { [key: string]: number } = {
-    Digit1: 0x1,
-    Digit2: 0x2,
-    Digit3: 0x3,
-    Digit4: 0xC,
-    KeyQ: 0x4,
-    KeyW: 0x5,
-    KeyE: 0x6,
-    KeyR: 0xD,
-    KeyA: 0x7,
-    KeyS: 0x8,
-    KeyD: 0x9,
-    KeyF: 0xE,
-    KeyZ: 0xA,
-    KeyX: 0x0,
-    KeyC: 0xB,
-    KeyV: 0xF,
-} as const;
-
-type KeyMapType = keyof typeof KEY_MAP;
+import init, { Chip8, DirtyRegion, Platform } from "@/utils/wasm/chip_8";
+
+// Keys of the numeric keypad are named by position so they do not depend on Num Lock
+const hostKey = (event: KeyboardEvent) =>
+    event.location === KeyboardEvent.DOM_KEY_LOCATION_NUMPAD ? event.code : event.key;
 
 export default function Canvas() {
     const canvasRef = useRef<HTMLCanvasElement>(null);
+    const memoryRef = useRef<WebAssembly.Memory | null>(null);
     const [engine, setEngine] = useState<Chip8 | null>(null);
     const [audioContext, setAudioContext] = useState<AudioContext | null>(null);
-    const [oscillator, setOscillator] = useState<OscillatorNode | null>(null);
-    const [soundActive, setSoundActive] = useState(false);
 
     const initializeEngine = async (setEngine: (engine: Chip8) => void) => {
         try {
-            await init();
+            const wasm = await init();
+            memoryRef.current = wasm.memory;
 
-            const engine = new Chip8();
+            const engine = new Chip8(Platform.Chip48);
+            engine.set_auto_configure(true);
             setEngine(engine);
 
             const response = await fetch("/games/PONG(1P)");
@@ -52,25 +36,6 @@ export default function Canvas() {
         initializeEngine(setEngine);
     }, []);
 
-    const handleSound = () => {
-        if (soundActive) {
-            if (!oscillator && audioContext) {
-                const osc = audioContext.createOscillator();
-                osc.type = "square";
-                osc.frequency.setValueAtTime(440, audioContext.currentTime);
-                osc.connect(audioContext.destination);
-                osc.start();
-                setOscillator(osc);
-            }
-        } else {
-            if (oscillator) {
-                oscillator.stop();
-                oscillator.disconnect();
-                setOscillator(null);
-            }
-        }
-    }
-
     const handleUserInteractionAudio = () => {
         if (!audioContext && typeof AudioContext !== "undefined") {
             const audioCtx = new AudioContext();
@@ -79,24 +44,27 @@ export default function Canvas() {
     };
 
     useEffect(() => {
-        if (engine) handleSound();
-    }, [engine, soundActive]);
+        if (!engine || !audioContext) return;
+
+        const processor = audioContext.createScriptProcessor(1024, 0, 1);
+
+        processor.onaudioprocess = (event) => {
+            const output = event.outputBuffer.getChannelData(0);
+            engine.fill_audio(output, audioContext.sampleRate);
+        };
+
+        processor.connect(audioContext.destination);
+
+        return () => processor.disconnect();
+    }, [engine, audioContext]);
 
     useEffect(() => {
         const handleKeyDown = (event: KeyboardEvent) => {
-            const key = event.code as KeyMapType;
-
-            if (KEY_MAP[key] !== undefined) {
-                engine?.key_down(KEY_MAP[key]);
-            }
+            engine?.host_key_down(hostKey(event));
         };
 
         const handleKeyUp = (event: KeyboardEvent) => {
-            const key = event.code as KeyMapType;
-
-            if (KEY_MAP[key] !== undefined) {
-                engine?.key_up(KEY_MAP[key]);
-            }
+            engine?.host_key_up(hostKey(event));
         };
 
         window.addEventListener("keydown", handleKeyDown);
@@ -114,41 +82,46 @@ export default function Canvas() {
 
         if (!engine || !canvas || !context) return;
 
-        const width = engine.get_width();
-        const height = engine.get_height();
-
-        canvas.width = width;
-        canvas.height = height;
+        canvas.width = engine.get_width();
+        canvas.height = engine.get_height();
 
         context.fillStyle = "FFFFFF";
-        context.fillRect(0, 0, width, height);
+        context.fillRect(0, 0, canvas.width, canvas.height);
 
         const renderFrame = () => {
-            const executeCycle = (steps: number) => {
-                for (let i = 0; i < steps; i++) {
-                    engine.execute_cycle();
+            const drawDisplay = (region: Pick<DirtyRegion, "x" | "y" | "width" | "height">) => {
+                const pointer = engine.render_rgba_ptr();
+                const width = engine.get_rgba_width();
+                const height = engine.get_rgba_height();
+
+                if (canvas.width !== width || canvas.height !== height) {
+                    canvas.width = width;
+                    canvas.height = height;
                 }
 
-                engine.decrement_timer();
+                const pixels = new Uint8ClampedArray(memoryRef.current!.buffer, pointer, width * height * 4);
+                const image = new ImageData(pixels, width, height);
+                const scale = engine.get_scale();
+
+                context.putImageData(
+                    image, 0, 0,
+                    region.x * scale, region.y * scale, region.width * scale, region.height * scale,
+                );
             };
 
-            const drawDisplay = () => {
-                const display = engine.get_display();
-                const image = context.createImageData(width, height);
+            // The summary lives in the WebAssembly memory until it is freed
+            engine.run_frame().free();
 
-                for (let i = 0; i < width * height; i++) {
-                    image.data[i * 4] = display[i] === 1 ? 0x33 : 0;
-                    image.data[i * 4 + 1] = display[i] === 1 ? 0xff : 0;
-                    image.data[i * 4 + 2] = display[i] === 1 ? 0x66 : 0;
-                    image.data[i * 4 + 3] = 255;
-                }
+            const region = engine.take_dirty_region();
 
-                context.putImageData(image, 0, 0);
+            if (engine.is_fading()) {
+                // Pixels that turned off keep fading out anywhere on the display
+                drawDisplay({ x: 0, y: 0, width: engine.get_width(), height: engine.get_height() });
+            } else if (region) {
+                drawDisplay(region);
             }
 
-            executeCycle(12);
-            drawDisplay();
-            setSoundActive(engine.is_sound_active());
+            region?.free();
         };
 
         const interval = setInterval(renderFrame, 1000 / 60);